@@ -1,6 +1,12 @@
-use super::layout::Bounds;
+use super::layout::{Bounds, Position};
 
 pub trait Artist {
     fn calculate_bounds(&self, window: xcb::Window) -> Option<Bounds>;
     fn draw(&self, window: xcb::Window);
+
+    // The managed window represented at a position within the decoration, if clicking there
+    // should focus it
+    fn window_at(&self, _position: Position) -> Option<xcb::Window> {
+        None
+    }
 }
@@ -0,0 +1,284 @@
+use std::{iter::Peekable, str::Chars};
+
+// Grammar:
+//
+//   commands := command (';' command)*
+//   command  := word+
+//   word     := (bare | '"' double-quoted '"' | '\'' single-quoted '\'')+
+//
+// Words are separated by whitespace. A backslash escapes the following character in bare and
+// double-quoted text (\n and \t have their usual meaning), single-quoted text is literal.
+// Placeholders such as {focused_window} or {prompt:Window title} may appear in bare and
+// double-quoted text, and extend to the next '}' whatever it contains.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordPart {
+    Text(String),
+    Placeholder(String), // without the braces
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub parts: Vec<WordPart>,
+    pub is_quoted: bool, // contains quotes or escapes
+}
+
+impl Word {
+    pub fn text(text: &str) -> Word {
+        Word {
+            parts: vec![WordPart::Text(text.to_owned())],
+            is_quoted: true,
+        }
+    }
+
+    // The word as written, minus quoting
+    pub fn to_text(&self) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                WordPart::Text(text) => text.clone(),
+                WordPart::Placeholder(name) => format!("{{{}}}", name),
+            })
+            .collect()
+    }
+
+    fn push_char(&mut self, c: char) {
+        match self.parts.last_mut() {
+            Some(WordPart::Text(text)) => text.push(c),
+            _ => self.parts.push(WordPart::Text(c.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub command: String,
+    pub words: Vec<Word>,
+}
+
+pub fn parse(input: &str) -> Result<Vec<Invocation>, String> {
+    let mut invocations = Vec::new();
+    let mut words = Vec::new();
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        match chars.peek() {
+            None => break,
+            Some(';') => {
+                chars.next();
+                finish_invocation(&mut invocations, &mut words);
+            }
+            Some(_) => words.push(parse_word(&mut chars)?),
+        }
+    }
+    finish_invocation(&mut invocations, &mut words);
+    Ok(invocations)
+}
+
+fn finish_invocation(invocations: &mut Vec<Invocation>, words: &mut Vec<Word>) {
+    // Empty commands, e.g. from a trailing ';', are ignored
+    if !words.is_empty() {
+        let mut words = words.drain(..);
+        let command = words.next().unwrap().to_text();
+        invocations.push(Invocation {
+            command,
+            words: words.collect(),
+        });
+    }
+}
+
+fn parse_word(chars: &mut Peekable<Chars>) -> Result<Word, String> {
+    let mut word = Word {
+        parts: Vec::new(),
+        is_quoted: false,
+    };
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() || c == ';' {
+            break;
+        }
+        chars.next();
+        match c {
+            '\\' => {
+                word.is_quoted = true;
+                word.push_char(parse_escape(chars)?);
+            }
+            '{' => word.parts.push(parse_placeholder(chars)?),
+            '"' => {
+                word.is_quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => word.push_char(parse_escape(chars)?),
+                        Some('{') => word.parts.push(parse_placeholder(chars)?),
+                        Some(c) => word.push_char(c),
+                        None => return Err(String::from("Unterminated double-quoted string")),
+                    }
+                }
+            }
+            '\'' => {
+                word.is_quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push_char(c),
+                        None => return Err(String::from("Unterminated single-quoted string")),
+                    }
+                }
+            }
+            _ => word.push_char(c),
+        }
+    }
+    Ok(word)
+}
+
+fn parse_placeholder(chars: &mut Peekable<Chars>) -> Result<WordPart, String> {
+    let mut name = String::new();
+    loop {
+        match chars.next() {
+            Some('}') => return Ok(WordPart::Placeholder(name)),
+            Some(c) => name.push(c),
+            None => return Err(String::from("Unterminated placeholder")),
+        }
+    }
+}
+
+fn parse_escape(chars: &mut Peekable<Chars>) -> Result<char, String> {
+    match chars.next() {
+        Some('n') => Ok('\n'),
+        Some('t') => Ok('\t'),
+        Some(c) => Ok(c),
+        None => Err(String::from("Incomplete escape sequence")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(input: &str) -> Vec<Vec<WordPart>> {
+        parse(input).unwrap()[0]
+            .words
+            .iter()
+            .map(|w| w.parts.clone())
+            .collect()
+    }
+
+    fn text(text: &str) -> WordPart {
+        WordPart::Text(text.to_owned())
+    }
+
+    fn placeholder(name: &str) -> WordPart {
+        WordPart::Placeholder(name.to_owned())
+    }
+
+    fn texts(input: &str) -> Vec<(String, Vec<String>)> {
+        parse(input)
+            .unwrap()
+            .iter()
+            .map(|i| {
+                (
+                    i.command.clone(),
+                    i.words.iter().map(|w| w.to_text()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn invocation(command: &str, words: &[&str]) -> (String, Vec<String>) {
+        (
+            command.to_owned(),
+            words.iter().map(|w| (*w).to_owned()).collect(),
+        )
+    }
+
+    #[test]
+    fn words_are_separated_by_whitespace() {
+        assert_eq!(
+            texts("  set_ratio:\t 500  "),
+            vec![invocation("set_ratio:", &["500"])]
+        );
+    }
+
+    #[test]
+    fn commands_are_chained_with_semicolons() {
+        assert_eq!(
+            texts("undo;redo ; ;switch_to_workspace_named: 2;"),
+            vec![
+                invocation("undo", &[]),
+                invocation("redo", &[]),
+                invocation("switch_to_workspace_named:", &["2"]),
+            ]
+        );
+        assert!(parse(" ; ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn quotes_keep_whitespace_and_semicolons() {
+        assert_eq!(
+            texts(r#"rename_workspace: "a b;c" 'd "e"' f"g"h"#),
+            vec![invocation(
+                "rename_workspace:",
+                &["a b;c", r#"d "e""#, "fgh"]
+            )]
+        );
+    }
+
+    #[test]
+    fn backslash_escapes_outside_single_quotes() {
+        assert_eq!(
+            texts(r#"rename_workspace: a\ b "c\"d\n" 'e\f'"#),
+            vec![invocation("rename_workspace:", &["a b", "c\"d\n", r"e\f"])]
+        );
+    }
+
+    #[test]
+    fn quotes_and_escapes_mark_words_as_quoted() {
+        let words = parse(r#"create_workspace: a "b" 'c' \d"#).unwrap()[0]
+            .words
+            .iter()
+            .map(|w| w.is_quoted)
+            .collect::<Vec<_>>();
+        assert_eq!(words, vec![false, true, true, true]);
+    }
+
+    #[test]
+    fn unterminated_quotes_and_escapes_are_errors() {
+        assert!(parse(r#"rename_workspace: "a"#).is_err());
+        assert!(parse("rename_workspace: 'a").is_err());
+        assert!(parse("rename_workspace: a\\").is_err());
+    }
+
+    #[test]
+    fn placeholder_may_contain_spaces() {
+        assert_eq!(
+            parts("rename_workspace: {prompt:New name}"),
+            vec![vec![placeholder("prompt:New name")]]
+        );
+    }
+
+    #[test]
+    fn placeholder_is_parsed_in_double_quotes() {
+        assert_eq!(
+            parts(r#"rename_workspace: "web {prompt:Suffix; or not}""#),
+            vec![vec![text("web "), placeholder("prompt:Suffix; or not")]]
+        );
+    }
+
+    #[test]
+    fn placeholder_is_literal_in_single_quotes_or_escaped() {
+        assert_eq!(
+            parts(r"rename_workspace: '{focused_window}' \{focused_window}"),
+            vec![
+                vec![text("{focused_window}")],
+                vec![text("{focused_window}")]
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_placeholder_is_an_error() {
+        assert!(parse("focus_on_window: {class:URxvt").is_err());
+    }
+}
@@ -1,10 +1,360 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentType {
+    String,
+    Number,
+    Window,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Argument {
+    String(String),
+    Number(i64),
+    Window(xcb::Window),
+}
+
+impl Argument {
+    pub fn parse(argument_type: ArgumentType, text: &str) -> Result<Argument, String> {
+        match argument_type {
+            ArgumentType::String => Ok(Argument::String(text.to_owned())),
+            ArgumentType::Number => text
+                .parse::<i64>()
+                .map(Argument::Number)
+                .map_err(|_| format!("Expected a number but found '{}'", text)),
+            ArgumentType::Window => {
+                let result = if text.starts_with("0x") || text.starts_with("0X") {
+                    u32::from_str_radix(&text[2..], 16)
+                } else {
+                    text.parse::<u32>()
+                };
+                result
+                    .map(Argument::Window)
+                    .map_err(|_| format!("Expected a window id but found '{}'", text))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Argument {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Argument::String(value) => write!(f, "{}", value),
+            Argument::Number(value) => write!(f, "{}", value),
+            Argument::Window(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl ArgumentType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArgumentType::String => "string",
+            ArgumentType::Number => "number",
+            ArgumentType::Window => "window",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    pub argument_type: ArgumentType,
+    pub allowed_values: Vec<String>,
+    pub is_optional: bool,
+}
+
+impl Parameter {
+    pub fn new(name: &str, argument_type: ArgumentType) -> Parameter {
+        Parameter {
+            name: name.to_owned(),
+            argument_type,
+            allowed_values: Default::default(),
+            is_optional: false,
+        }
+    }
+
+    // Optional parameters may only follow the required ones
+    pub fn optional(mut self) -> Parameter {
+        self.is_optional = true;
+        self
+    }
+
+    // Allowed values are a hint for completion - they are not enforced when binding
+    pub fn with_allowed_values<T: ToString>(mut self, values: &[T]) -> Parameter {
+        self.allowed_values = values.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"name\":{},\"type\":{},\"optional\":{},\"allowed_values\":[{}]}}",
+            json_string(&self.name),
+            json_string(self.argument_type.name()),
+            self.is_optional,
+            self.allowed_values
+                .iter()
+                .map(|v| json_string(v))
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandDescriptor {
+    pub name: String,
+    pub scope: String,
+    pub help: String,
+    pub parameters: Vec<Parameter>,
+}
+
+impl CommandDescriptor {
+    pub fn new(name: &str, help: &str) -> CommandDescriptor {
+        CommandDescriptor {
+            name: name.to_owned(),
+            scope: Default::default(),
+            help: help.to_owned(),
+            parameters: Default::default(),
+        }
+    }
+
+    pub fn with_parameter(mut self, parameter: Parameter) -> CommandDescriptor {
+        self.parameters.push(parameter);
+        self
+    }
+
+    pub fn prefixed(&self, prefix: &str) -> CommandDescriptor {
+        CommandDescriptor {
+            name: format!("{}{}", prefix, self.name),
+            scope: format!("{}{}", prefix, self.scope),
+            ..self.clone()
+        }
+    }
+
+    pub fn bind(&self, words: &[String]) -> Result<Vec<Argument>, String> {
+        let required_count = self.parameters.iter().filter(|p| !p.is_optional).count();
+        if words.len() < required_count || words.len() > self.parameters.len() {
+            return Err(if required_count == self.parameters.len() {
+                format!(
+                    "{} expects {} argument(s) but was given {}",
+                    self.name,
+                    self.parameters.len(),
+                    words.len()
+                )
+            } else {
+                format!(
+                    "{} expects {} to {} arguments but was given {}",
+                    self.name,
+                    required_count,
+                    self.parameters.len(),
+                    words.len()
+                )
+            });
+        }
+        self.parameters
+            .iter()
+            .zip(words)
+            .map(|(parameter, word)| Argument::parse(parameter.argument_type, word))
+            .collect()
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"name\":{},\"command\":{},\"scope\":{},\"help\":{},\"parameters\":[{}]}}",
+            json_string(&self.name),
+            json_string(&self.name[self.scope.len()..]),
+            json_string(self.scope.trim_end_matches('/')),
+            json_string(&self.help),
+            self.parameters
+                .iter()
+                .map(|p| p.to_json())
+                .collect::<Vec<_>>()
+                .join(",")
+        )
+    }
+}
+
+// Adds the commands that aren't already described
+pub fn extend_commands(
+    commands: &mut Vec<CommandDescriptor>,
+    other_commands: Vec<CommandDescriptor>,
+) {
+    for command in other_commands {
+        if !commands.iter().any(|c| c.name == command.name) {
+            commands.push(command);
+        }
+    }
+}
+
+pub fn catalog_to_json(commands: &[CommandDescriptor]) -> String {
+    format!(
+        "[{}]",
+        commands
+            .iter()
+            .map(|c| c.to_json())
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
+fn json_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandResult {
+    Changed,
+    Unchanged,
+    Error(String),
+}
+
+impl CommandResult {
+    pub fn error(message: &str) -> CommandResult {
+        CommandResult::Error(message.to_owned())
+    }
+
+    pub fn unknown_command(command: &str) -> CommandResult {
+        CommandResult::Error(format!("Unknown command: {}", command))
+    }
+
+    pub fn invalid_arguments(command: &str) -> CommandResult {
+        CommandResult::Error(format!("Invalid arguments for {}", command))
+    }
+
+    pub fn changed_if(is_changed: bool) -> CommandResult {
+        if is_changed {
+            CommandResult::Changed
+        } else {
+            CommandResult::Unchanged
+        }
+    }
+
+    pub fn needs_layout(&self) -> bool {
+        *self == CommandResult::Changed
+    }
+}
+
 pub trait Commands {
-    fn get_commands(&self) -> Vec<String> {
+    // The commands that apply in the current state
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
         Default::default()
     }
 
-    fn execute_command(&mut self, command: &str, _args: &[&str]) -> bool {
-        eprintln!("Unhandled command: {}", command);
-        false
+    // Also the commands that don't apply right now, so that their arguments can still be
+    // bound, e.g. in a macro, and executing them can explain why nothing happened
+    fn get_all_commands(&self) -> Vec<CommandDescriptor> {
+        self.get_commands()
+    }
+
+    fn execute_command(&mut self, command: &str, _args: &[Argument]) -> CommandResult {
+        CommandResult::unknown_command(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arguments_are_parsed_by_type() {
+        assert_eq!(
+            Argument::parse(ArgumentType::String, "-12"),
+            Ok(Argument::String("-12".into()))
+        );
+        assert_eq!(
+            Argument::parse(ArgumentType::Number, "-12"),
+            Ok(Argument::Number(-12))
+        );
+        assert!(Argument::parse(ArgumentType::Number, "12px").is_err());
+    }
+
+    #[test]
+    fn windows_are_parsed_as_decimal_or_hexadecimal() {
+        assert_eq!(
+            Argument::parse(ArgumentType::Window, "4194311"),
+            Ok(Argument::Window(4194311))
+        );
+        assert_eq!(
+            Argument::parse(ArgumentType::Window, "0x400007"),
+            Ok(Argument::Window(0x400007))
+        );
+        assert!(Argument::parse(ArgumentType::Window, "-1").is_err());
+        assert!(Argument::parse(ArgumentType::Window, "0x").is_err());
+    }
+
+    #[test]
+    fn binding_checks_the_number_of_arguments() {
+        let descriptor = CommandDescriptor::new("delete_workspace:", "")
+            .with_parameter(Parameter::new("workspace", ArgumentType::String))
+            .with_parameter(Parameter::new("destination", ArgumentType::String).optional());
+        let words = |words: &[&str]| words.iter().map(|w| (*w).to_owned()).collect::<Vec<_>>();
+        assert!(descriptor.bind(&words(&[])).is_err());
+        assert_eq!(
+            descriptor.bind(&words(&["a"])),
+            Ok(vec![Argument::String("a".into())])
+        );
+        assert_eq!(descriptor.bind(&words(&["a", "b"])).map(|a| a.len()), Ok(2));
+        assert!(descriptor.bind(&words(&["a", "b", "c"])).is_err());
+    }
+
+    #[test]
+    fn extending_commands_keeps_existing_descriptions() {
+        let mut commands = vec![CommandDescriptor::new("undo", "Undo")];
+        extend_commands(
+            &mut commands,
+            vec![
+                CommandDescriptor::new("undo", "Something else"),
+                CommandDescriptor::new("redo", "Redo"),
+            ],
+        );
+        assert_eq!(
+            commands,
+            vec![
+                CommandDescriptor::new("undo", "Undo"),
+                CommandDescriptor::new("redo", "Redo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_strings_are_escaped() {
+        assert_eq!(json_string("plain"), r#""plain""#);
+        assert_eq!(
+            json_string("say \"hi\"\\\n\t\u{1}é"),
+            r#""say \"hi\"\\\n\t\u0001é""#
+        );
+    }
+
+    #[test]
+    fn catalog_describes_commands_and_parameters() {
+        let commands = vec![
+            CommandDescriptor::new("undo", "Undo \"it\""),
+            CommandDescriptor::new("set_ratio:", "Set the ratio")
+                .with_parameter(
+                    Parameter::new("thousandths", ArgumentType::Number).with_allowed_values(&[500]),
+                )
+                .prefixed("layout/0/"),
+        ];
+        assert_eq!(
+            catalog_to_json(&commands),
+            concat!(
+                r#"[{"name":"undo","command":"undo","scope":"","help":"Undo \"it\"","parameters":[]},"#,
+                r#"{"name":"layout/0/set_ratio:","command":"set_ratio:","scope":"layout/0","#,
+                r#""help":"Set the ratio","parameters":[{"name":"thousandths","type":"number","#,
+                r#""optional":false,"allowed_values":["500"]}]}]"#
+            )
+        );
     }
 }
@@ -1,7 +1,7 @@
 mod default;
 mod user;
 
-use super::{layout::*, workspace::Workspace};
+use super::{layout::*, macros::Macro, workspace::Workspace};
 
 pub trait ConfigurationProvider {
     fn root(&self) -> &dyn ConfigurationProvider;
@@ -10,14 +10,44 @@ pub trait ConfigurationProvider {
         default::workspaces(self.root())
     }
 
+    // Also used for workspaces created while running
+    fn workspace(&self, name: &str) -> Workspace {
+        default::workspace(self.root(), name)
+    }
+
     fn layouts(&self) -> Vec<layout_root::LayoutRoot> {
         default::layouts(self.root())
     }
 
+    // The layouts a workspace can switch between, which must not be empty
+    fn workspace_layouts(&self, workspace_name: &str) -> Vec<layout_root::LayoutRoot> {
+        default::workspace_layouts(self.root(), workspace_name)
+    }
+
+    // The name of the layout a workspace starts with, or None for the first one
+    fn initial_layout(&self, workspace_name: &str) -> Option<String> {
+        default::initial_layout(self.root(), workspace_name)
+    }
+
     fn layout_root(&self, name: &str, child: Box<dyn Layout>) -> layout_root::LayoutRoot {
         default::layout_root(self.root(), name, child)
     }
 
+    // Whether switching to the current workspace by name returns to the previous one
+    fn workspace_back_and_forth(&self) -> bool {
+        default::workspace_back_and_forth(self.root())
+    }
+
+    // Whether switching to the next or previous workspace in order passes over empty ones
+    fn skip_empty_workspaces(&self) -> bool {
+        default::skip_empty_workspaces(self.root())
+    }
+
+    // Macros that fail to parse are reported and left out
+    fn macros(&self) -> Vec<Result<Macro, String>> {
+        default::macros(self.root())
+    }
+
     fn classify_window(
         &self,
         window: xcb::Window,
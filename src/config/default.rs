@@ -1,12 +1,58 @@
-use crate::{config::*, connection::*, layout::*, workspace::Workspace};
+use crate::{
+    commands::{ArgumentType, Parameter},
+    config::*,
+    connection::*,
+    layout::*,
+    macros::Macro,
+    workspace::Workspace,
+};
 
 pub fn workspaces(configuration: &dyn ConfigurationProvider) -> Vec<Workspace> {
     ["1", "2", "3", "4", "5", "6", "7", "8", "9"]
         .iter()
-        .map(|name| Workspace::new(name, configuration.layouts()))
+        .map(|name| configuration.workspace(name))
         .collect()
 }
 
+// Workspaces cannot be without layouts, so a configuration that gives none gets the built-in
+// ones instead, which are never empty
+pub fn workspace(configuration: &dyn ConfigurationProvider, name: &str) -> Workspace {
+    let mut layouts = configuration.workspace_layouts(name);
+    if layouts.is_empty() {
+        log::error!("No layouts are configured for workspace {}", name);
+        layouts = self::layouts(configuration);
+    }
+    let workspace = Workspace::new(name, layouts);
+    match configuration.initial_layout(name) {
+        Some(layout_name) if workspace.layouts.iter().any(|l| l.name() == layout_name) => {
+            workspace.with_initial_layout(&layout_name)
+        }
+        Some(layout_name) => {
+            log::error!(
+                "The initial layout {} of workspace {} is not one of its layouts",
+                layout_name,
+                name
+            );
+            workspace
+        }
+        None => workspace,
+    }
+}
+
+pub fn workspace_layouts(
+    configuration: &dyn ConfigurationProvider,
+    _workspace_name: &str,
+) -> Vec<layout_root::LayoutRoot> {
+    configuration.layouts()
+}
+
+pub fn initial_layout(
+    _configuration: &dyn ConfigurationProvider,
+    _workspace_name: &str,
+) -> Option<String> {
+    None
+}
+
 pub fn layouts(configuration: &dyn ConfigurationProvider) -> Vec<layout_root::LayoutRoot> {
     vec![
         configuration.layout_root(
@@ -17,11 +63,71 @@ pub fn layouts(configuration: &dyn ConfigurationProvider) -> Vec<layout_root::La
             "monad_wide_top",
             monad_layout::new_linear(Direction::Increasing, Axis::Y, 0.75, 1),
         ),
+        configuration.layout_root(
+            "monad_adaptive",
+            dynamic_layout::switch_on_available_size(
+                Axis::X,
+                1600,
+                monad_layout::new_linear(Direction::Increasing, Axis::Y, 0.75, 1),
+                monad_layout::new_linear(Direction::Decreasing, Axis::X, 0.75, 1),
+            ),
+        ),
+        configuration.layout_root(
+            "monocle_or_monad",
+            dynamic_layout::switch_on_window_count(
+                1,
+                monocle_layout::new(),
+                monad_layout::new_linear(Direction::Decreasing, Axis::X, 0.75, 1),
+            ),
+        ),
+        configuration.layout_root(
+            "columns_or_tabbed",
+            dynamic_layout::switch_on_prorata_size(
+                Axis::X,
+                400,
+                tabbed_layout::new(),
+                linear_layout::new(Direction::Increasing, Axis::X),
+            ),
+        ),
         configuration.layout_root(
             "monad_stacked",
             monad_layout::new_stack(Direction::Decreasing, Axis::X, 0.75, 1),
         ),
+        configuration.layout_root(
+            "monad_tabbed",
+            monad_layout::new_tabbed(Direction::Decreasing, Axis::X, 0.75, 1),
+        ),
         configuration.layout_root("stacked", stack_layout::new()),
+        configuration.layout_root("tabbed", tabbed_layout::new()),
+        configuration.layout_root("monocle", monocle_layout::new()),
+        configuration.layout_root("centered_master", centered_master_layout::new(0.5, 1)),
+        configuration.layout_root("grid", grid_layout::new().spanning_last_line()),
+        configuration.layout_root(
+            "grid_two_rows",
+            grid_layout::new()
+                .with_rows(2)
+                .column_major()
+                .spanning_last_line(),
+        ),
+        configuration.layout_root("bsp", bsp_layout::new()),
+        configuration.layout_root(
+            "browsers_and_terminals",
+            partition_layout::new(
+                Direction::Increasing,
+                Axis::X,
+                0.5,
+                partition_layout::any(vec![
+                    partition_layout::with_class("firefox"),
+                    partition_layout::with_class("chromium"),
+                    partition_layout::with_title_containing("Mozilla Firefox"),
+                    partition_layout::with_tag("browser"),
+                ]),
+                tabbed_layout::new(),
+                grid_layout::new().with_columns(2),
+            ),
+        ),
+        configuration.layout_root("dwindle", dwindle_layout::new_dwindle(0.5)),
+        configuration.layout_root("spiral", dwindle_layout::new_spiral(0.5)),
     ]
 }
 
@@ -32,15 +138,33 @@ pub fn layout_root(
 ) -> layout_root::LayoutRoot {
     layout_root::new(
         name,
-        add_window_selector_labels::new(add_border::new(
+        add_window_selector_labels::new(add_border::new_smart(
             1,
             (127, 127, 127),
             (0, 255, 0),
-            floating_layout::new(add_gaps::new(5, 5, child)),
+            floating_layout::new(add_zoom::new(add_gaps::new_smart(5, 5, child))),
         )),
     )
 }
 
+pub fn workspace_back_and_forth(_configuration: &dyn ConfigurationProvider) -> bool {
+    false
+}
+
+pub fn skip_empty_workspaces(_configuration: &dyn ConfigurationProvider) -> bool {
+    false
+}
+
+pub fn macros(_configuration: &dyn ConfigurationProvider) -> Vec<Result<Macro, String>> {
+    vec![Macro::new(
+        "move_focused_window_and_follow:",
+        "Move the focused window to another workspace and switch to it",
+        vec![Parameter::new("workspace", ArgumentType::String)],
+        "move_focused_window_to_workspace_named: $workspace; \
+         switch_to_workspace_named: $workspace",
+    )]
+}
+
 pub fn classify_window(
     _configuration: &dyn ConfigurationProvider,
     _window: xcb::Window,
@@ -1,14 +1,28 @@
 use cairo::XCBSurface;
 use lazy_static::lazy_static;
+use std::time::{Duration, Instant};
+
+fn connection_and_screen_number() -> &'static (xcb::Connection, i32) {
+    static mut CONNECTION: Option<(xcb::Connection, i32)> = None;
+    unsafe { CONNECTION.get_or_insert_with(|| xcb::Connection::connect(None).unwrap()) }
+}
 
 pub fn connection() -> &'static xcb::Connection {
-    static mut CONNECTION: Option<xcb::Connection> = None;
-    unsafe {
-        CONNECTION.get_or_insert_with(|| {
-            let (connection, _screen_number) = xcb::Connection::connect(None).unwrap();
-            connection
-        })
-    }
+    &connection_and_screen_number().0
+}
+
+// The screen given by the display name, e.g. 1 for ":0.1"
+pub fn screen_number() -> i32 {
+    connection_and_screen_number().1
+}
+
+// The screen whose root window is managed
+pub fn screen() -> xcb::Screen<'static> {
+    connection()
+        .get_setup()
+        .roots()
+        .nth(screen_number() as usize)
+        .unwrap()
 }
 
 pub fn get_atom(name: &str) -> u32 {
@@ -20,6 +34,9 @@ pub fn get_atom(name: &str) -> u32 {
 
 lazy_static! {
     pub static ref ATOM_UTF8_STRING: u32 = get_atom("UTF8_STRING");
+    pub static ref ATOM_MANAGER: u32 = get_atom("MANAGER");
+    // The manager selection for the screen, see ICCCM section 2.8
+    pub static ref ATOM_WM_SN: u32 = get_atom(&format!("WM_S{}", screen_number()));
     //
     pub static ref ATOM__NET_WM_NAME: u32 = get_atom("_NET_WM_NAME");
     pub static ref ATOM__NET_SUPPORTED: u32 = get_atom("_NET_SUPPORTED");
@@ -85,8 +102,12 @@ lazy_static! {
     pub static ref ATOM__NET_WM_ACTION_BELOW: u32 = get_atom("_NET_WM_ACTION_BELOW");
     //
     pub static ref ATOM_CERAMIC_COMMAND: u32 = get_atom("CERAMIC_COMMAND");
+    pub static ref ATOM_CERAMIC_COMMAND_RESULT: u32 = get_atom("CERAMIC_COMMAND_RESULT");
     pub static ref ATOM_CERAMIC_AVAILABLE_COMMANDS: u32 = get_atom("CERAMIC_AVAILABLE_COMMANDS");
+    pub static ref ATOM_CERAMIC_COMMAND_CATALOG: u32 = get_atom("CERAMIC_COMMAND_CATALOG");
     pub static ref ATOM_CERAMIC_SELECTOR_LABEL: u32 = get_atom("CERAMIC_SELECTOR_LABEL");
+    pub static ref ATOM_CERAMIC_NOTIFICATION_TIMEOUT: u32 =
+        get_atom("CERAMIC_NOTIFICATION_TIMEOUT");
 }
 
 pub fn set_cardinal_property(window: xcb::Window, name_atom: u32, value: u32) {
@@ -320,7 +341,7 @@ pub fn get_atoms_property(window: xcb::Window, name_atom: u32) -> Vec<u32> {
 }
 
 pub fn grab_keyboard() {
-    let root = connection().get_setup().roots().nth(0).unwrap().root();
+    let root = screen().root();
     match xcb::xproto::grab_keyboard(
         connection(),
         false,
@@ -349,6 +370,56 @@ pub fn ungrab_keyboard() {
     connection().flush();
 }
 
+// See the X cursor font for glyph numbers, e.g. 34 is XC_crosshair
+pub fn grab_pointer_with_cursor(glyph: u16) -> bool {
+    let connection = connection();
+    let root = screen().root();
+
+    let font = connection.generate_id();
+    xcb::open_font(connection, font, "cursor");
+    let cursor = connection.generate_id();
+    xcb::create_glyph_cursor(
+        connection,
+        cursor,
+        font,
+        font,
+        glyph,
+        glyph + 1,
+        0,
+        0,
+        0,
+        0xffff,
+        0xffff,
+        0xffff,
+    );
+    xcb::close_font(connection, font);
+
+    let result = xcb::grab_pointer(
+        connection,
+        false,
+        root,
+        xcb::EVENT_MASK_BUTTON_PRESS as u16,
+        xcb::GRAB_MODE_ASYNC as u8,
+        xcb::GRAB_MODE_ASYNC as u8,
+        xcb::NONE,
+        cursor,
+        xcb::CURRENT_TIME,
+    )
+    .get_reply()
+    .map(|reply| reply.status() as u32 == xcb::GRAB_STATUS_SUCCESS)
+    .unwrap_or(false);
+
+    // The server keeps the cursor alive for as long as the grab uses it
+    xcb::free_cursor(connection, cursor);
+    connection.flush();
+    result
+}
+
+pub fn ungrab_pointer() {
+    xcb::ungrab_pointer(connection(), xcb::CURRENT_TIME);
+    connection().flush();
+}
+
 pub fn allow_events() {
     xcb::xproto::allow_events(
         connection(),
@@ -363,6 +434,47 @@ pub fn wait_for_event() -> Option<xcb::base::GenericEvent> {
     connection().wait_for_event()
 }
 
+// The window must have selected PROPERTY_CHANGE events. Any other events that arrive in the
+// meantime are discarded, so this is only suitable before the event loop is started.
+pub fn get_timestamp(window: xcb::Window) -> xcb::Timestamp {
+    let connection = connection();
+    xcb::change_property(
+        connection,
+        xcb::PROP_MODE_APPEND as u8,
+        window,
+        *ATOM__NET_WM_NAME,
+        *ATOM_UTF8_STRING,
+        8,
+        &[0u8; 0],
+    );
+    connection.flush();
+    while let Some(e) = connection.wait_for_event() {
+        if e.response_type() & 0x7f == xcb::PROPERTY_NOTIFY {
+            let e: &xcb::PropertyNotifyEvent = unsafe { xcb::cast_event(&e) };
+            if e.window() == window {
+                return e.time();
+            }
+        }
+    }
+    xcb::CURRENT_TIME
+}
+
+pub fn wait_for_window_destruction(window: xcb::Window, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        while let Some(e) = connection().poll_for_event() {
+            if e.response_type() & 0x7f == xcb::DESTROY_NOTIFY {
+                let e: &xcb::DestroyNotifyEvent = unsafe { xcb::cast_event(&e) };
+                if e.window() == window {
+                    return true;
+                }
+            }
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    false
+}
+
 pub fn get_cairo_surface(window: xcb::Window) -> Result<cairo::Surface, xcb::GenericError> {
     let connection = connection();
 
@@ -375,7 +487,7 @@ pub fn get_cairo_surface(window: xcb::Window) -> Result<cairo::Surface, xcb::Gen
 
     let cairo_drawable = cairo::XCBDrawable(window);
 
-    let screen = connection.get_setup().roots().nth(0).unwrap();
+    let screen = screen();
     let mut visual = screen
         .allowed_depths()
         .filter(|d| d.depth() == screen.root_depth())
@@ -1,15 +1,28 @@
 pub mod add_border;
 pub mod add_gaps;
 pub mod add_window_selector_labels;
+pub mod add_zoom;
+pub mod bsp_layout;
+pub mod centered_master_layout;
+pub mod dwindle_layout;
+pub mod dynamic_layout;
 pub mod floating_layout;
 pub mod grid_layout;
 pub mod layout_root;
 pub mod linear_layout;
 pub mod monad_layout;
+pub mod monocle_layout;
+pub mod partition_layout;
 pub mod split_layout;
 pub mod stack_layout;
+pub mod tabbed_layout;
 
-use super::{artist::Artist, commands::Commands, connection::connection, window_data::WindowData};
+use super::{
+    artist::Artist,
+    commands::{CommandDescriptor, Commands},
+    connection::connection,
+    window_data::WindowData,
+};
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Copy)]
 pub struct Position {
@@ -80,12 +93,12 @@ impl Axis {
     //     }
     // }
 
-    // pub fn extract_size(&self, rect: &Bounds) -> u16 {
-    //     match self {
-    //         Axis::X => rect.size.width,
-    //         Axis::Y => rect.size.height,
-    //     }
-    // }
+    pub fn extract_size(&self, rect: &Bounds) -> u16 {
+        match self {
+            Axis::X => rect.size.width,
+            Axis::Y => rect.size.height,
+        }
+    }
 
     pub fn orthogonal(&self) -> Self {
         match self {
@@ -101,12 +114,190 @@ pub enum Direction {
     Decreasing,
 }
 
+// The first rect has the ratio of the size, and is at the start of the axis when increasing
+pub fn split_rect(rect: &Bounds, axis: Axis, direction: Direction, ratio: f64) -> (Bounds, Bounds) {
+    let mut rect_1 = *rect;
+    let mut rect_2 = *rect;
+
+    match axis {
+        Axis::X => {
+            rect_1.size.width = (rect.size.width as f64 * ratio).floor() as u16;
+            rect_2.size.width = rect.size.width - rect_1.size.width;
+            match direction {
+                Direction::Increasing => rect_2.origin.x = rect_1.max_x(),
+                Direction::Decreasing => rect_1.origin.x = rect_2.max_x(),
+            }
+        }
+        Axis::Y => {
+            rect_1.size.height = (rect.size.height as f64 * ratio).floor() as u16;
+            rect_2.size.height = rect.size.height - rect_1.size.height;
+            match direction {
+                Direction::Increasing => rect_2.origin.y = rect_1.max_y(),
+                Direction::Decreasing => rect_1.origin.y = rect_2.max_y(),
+            }
+        }
+    }
+
+    (rect_1, rect_2)
+}
+
+// A line between tiles that can be dragged to change the ratio of the layout that owns it, by
+// sending `set_ratio:` with the command prefix that reaches that layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct Boundary {
+    pub command_prefix: String,
+    pub axis: Axis, // the axis the boundary moves along
+    pub direction: Direction,
+    pub rect: Bounds, // the whole area being split
+    pub position: i16,
+}
+
+impl Boundary {
+    // The boundary between the rects made by split_rect
+    pub fn new(rect: &Bounds, axis: Axis, direction: Direction, ratio: f64) -> Boundary {
+        let (rect_1, _) = split_rect(rect, axis, direction, ratio);
+        let position = match (axis, direction) {
+            (Axis::X, Direction::Increasing) => rect_1.max_x(),
+            (Axis::X, Direction::Decreasing) => rect_1.origin.x,
+            (Axis::Y, Direction::Increasing) => rect_1.max_y(),
+            (Axis::Y, Direction::Decreasing) => rect_1.origin.y,
+        };
+        Boundary {
+            command_prefix: String::new(),
+            axis,
+            direction,
+            rect: *rect,
+            position,
+        }
+    }
+
+    pub fn prefixed(&self, prefix: &str) -> Boundary {
+        Boundary {
+            command_prefix: format!("{}{}", prefix, self.command_prefix),
+            ..self.clone()
+        }
+    }
+
+    pub fn is_near(&self, point: Position, tolerance: u16) -> bool {
+        let tolerance = tolerance as i16;
+        match self.axis {
+            Axis::X => {
+                (point.x - self.position).abs() <= tolerance
+                    && point.y >= self.rect.origin.y
+                    && point.y < self.rect.max_y()
+            }
+            Axis::Y => {
+                (point.y - self.position).abs() <= tolerance
+                    && point.x >= self.rect.origin.x
+                    && point.x < self.rect.max_x()
+            }
+        }
+    }
+
+    // The ratio that would put the boundary at the point
+    pub fn ratio_at(&self, point: Position) -> f64 {
+        let (offset, origin, size) = match self.axis {
+            Axis::X => (point.x, self.rect.origin.x, self.rect.size.width),
+            Axis::Y => (point.y, self.rect.origin.y, self.rect.size.height),
+        };
+        let first_size = match self.direction {
+            Direction::Increasing => offset as i32 - origin as i32,
+            Direction::Decreasing => origin as i32 + size as i32 - offset as i32,
+        };
+        first_size as f64 / size.max(1) as f64
+    }
+}
+
+// The adjustable parameters of a layout tree, e.g. split ratios, used to undo changes.
+// Each layout decides how to encode its own values and which children to include.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LayoutState {
+    pub values: Vec<f64>,
+    pub children: Vec<LayoutState>,
+}
+
+impl LayoutState {
+    pub fn new(values: Vec<f64>, children: Vec<LayoutState>) -> LayoutState {
+        LayoutState { values, children }
+    }
+}
+
+// Prefixes each child's commands with its index
+pub fn combine_child_commands(children: &[Vec<CommandDescriptor>]) -> Vec<CommandDescriptor> {
+    let mut result: Vec<CommandDescriptor> = Vec::new();
+    for (i, commands) in children.iter().enumerate() {
+        let prefix = format!("{}/", i);
+        for command in commands {
+            result.push(command.prefixed(&prefix));
+        }
+    }
+    result
+}
+
+pub fn combine_child_boundaries(children: Vec<Vec<Boundary>>) -> Vec<Boundary> {
+    children
+        .iter()
+        .enumerate()
+        .flat_map(|(i, boundaries)| {
+            let prefix = format!("{}/", i);
+            boundaries
+                .iter()
+                .map(|b| b.prefixed(&prefix))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// Settings the window manager applies to the layouts of every workspace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutSettings {
+    pub are_gaps_enabled: bool,
+    pub are_borders_enabled: bool,
+}
+
+impl Default for LayoutSettings {
+    fn default() -> LayoutSettings {
+        LayoutSettings {
+            are_gaps_enabled: true,
+            are_borders_enabled: true,
+        }
+    }
+}
+
 pub trait Layout: Commands {
     fn layout(
         &self,
         rect: &Bounds,
         windows: Vec<WindowData>,
     ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>);
+
+    fn get_state(&self) -> LayoutState {
+        Default::default()
+    }
+
+    fn set_state(&mut self, _state: &LayoutState) {}
+
+    // The workspace's focused window, given before every layout and layout command
+    fn set_focused_window(&mut self, _window: Option<xcb::Window>) {}
+
+    fn set_settings(&mut self, _settings: LayoutSettings) {}
+
+    // Must agree with the bounds that layout would give the same windows
+    fn get_boundaries(&self, _rect: &Bounds, _windows: &[WindowData]) -> Vec<Boundary> {
+        vec![]
+    }
+
+    // True when layout would give the tiled windows the same tile, e.g. a single window or a
+    // stack. Known before laying out, so that smart gaps can depend on it.
+    fn has_single_tile(&self, _rect: &Bounds, windows: &[WindowData]) -> bool {
+        windows.iter().filter(|w| !w.is_floating).count() == 1
+    }
+
+    // True when the window was given space in proportion to its weight by the most recent
+    // layout
+    fn is_weighted(&self, _window: xcb::Window) -> bool {
+        false
+    }
 }
 
 pub fn clear_window_order(windows: &mut [WindowData]) {
@@ -153,109 +344,69 @@ pub fn compute_window_order(windows: &mut [WindowData]) {
     }
 }
 
-//
-//------------------------------------------------------------------
-//
-
-// pub type BoxedLayoutPredicate = Box<Fn(&Bounds, &[&window::Window]) -> bool>;
-
-// #[derive(Clone)]
-// pub struct DynamicLayout<A, B> {
-//     pub predicate: Rc<BoxedLayoutPredicate>,
-//     pub children: (A, B),
-// }
-
-// impl<A: Layout, B: Layout> DynamicLayout<A, B> {
-//     pub fn make(predicate: BoxedLayoutPredicate, children: (A, B)) -> DynamicLayout<A, B> {
-//         DynamicLayout {
-//             predicate: Rc::new(predicate),
-//             children,
-//         }
-//     }
-
-//     pub fn switch_on_window_count(count: usize, children: (A, B)) -> DynamicLayout<A, B> {
-//         Self::make(Box::new(move |_, windows| windows.len() <= count), children)
-//     }
-
-//     pub fn switch_on_available_size(
-//         axis: Axis,
-//         size_break: u16,
-//         children: (A, B),
-//     ) -> DynamicLayout<A, B> {
-//         Self::make(
-//             Box::new(move |rect, _| axis.extract_size(rect) < size_break),
-//             children,
-//         )
-//     }
-
-//     pub fn switch_on_prorata_size(
-//         axis: Axis,
-//         size_break: u16,
-//         children: (A, B),
-//     ) -> DynamicLayout<A, B> {
-//         Self::make(
-//             Box::new(move |rect, windows| {
-//                 (axis.extract_size(rect) / windows.len() as u16) < size_break
-//             }),
-//             children,
-//         )
-//     }
-// }
-
-// impl<A: Layout, B: Layout> Layout for DynamicLayout<A, B> {
-//     fn layout(&self, rect: &Bounds, windows: &[&window::Window], artists: &mut Vec<Box<Artist>>) {
-//         if (self.predicate)(rect, windows) {
-//             self.children.0.layout(rect, windows)
-//         } else {
-//             self.children.1.layout(rect, windows)
-//         }
-//     }
-// }
-
-//
-//------------------------------------------------------------------
-//
-
-// pub type BoxedWindowPredicate = Box<Fn(&Bounds, usize, &window::Window) -> bool>;
-
-// #[derive(Clone)]
-// pub struct PredicateSelector<A> {
-//     pub predicate: Rc<BoxedWindowPredicate>,
-//     pub child: A,
-// }
-
-// impl<A: Layout> PredicateSelector<A> {
-//     pub fn passing(predicate: BoxedWindowPredicate, child: A) -> PredicateSelector<A> {
-//         PredicateSelector {
-//             predicate: Rc::new(predicate),
-//             child,
-//         }
-//     }
-
-//     pub fn failing(test: BoxedWindowPredicate, child: A) -> PredicateSelector<A> {
-//         Self::passing(
-//             Box::new(move |rect, index, window| !test(rect, index, window)),
-//             child,
-//         )
-//     }
-
-//     pub fn first(count: usize, child: A) -> PredicateSelector<A> {
-//         Self::passing(Box::new(move |_, index, _| index < count), child)
-//     }
-
-//     pub fn all_but_first(count: usize, child: A) -> PredicateSelector<A> {
-//         Self::passing(Box::new(move |_, index, _| index >= count), child)
-//     }
-// }
-
-// impl<A: Layout> Layout for PredicateSelector<A> {
-//     fn layout(&self, rect: &Bounds, windows: &[&window::Window], artists: &mut Vec<Box<Artist>>) {
-//         let filtered_windows: Vec<&window::Window> = windows
-//             .iter()
-//             .enumerate()
-//             .filter(|&(i, w)| (self.predicate)(rect, i, w))
-//             .map(|(_, &w)| w)
-//             .collect();
-//         (self.child).layout(rect, &filtered_windows)
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECT: Bounds = Bounds::new(10, 20, 101, 50);
+
+    #[test]
+    fn split_rect_puts_the_ratio_first() {
+        assert_eq!(
+            split_rect(&RECT, Axis::X, Direction::Increasing, 0.5),
+            (Bounds::new(10, 20, 50, 50), Bounds::new(60, 20, 51, 50))
+        );
+        assert_eq!(
+            split_rect(&RECT, Axis::X, Direction::Decreasing, 0.5),
+            (Bounds::new(61, 20, 50, 50), Bounds::new(10, 20, 51, 50))
+        );
+        assert_eq!(
+            split_rect(&RECT, Axis::Y, Direction::Increasing, 0.3),
+            (Bounds::new(10, 20, 101, 15), Bounds::new(10, 35, 101, 35))
+        );
+    }
+
+    #[test]
+    fn boundary_lies_between_the_split_rects() {
+        let boundary = Boundary::new(&RECT, Axis::X, Direction::Increasing, 0.5);
+        assert_eq!(boundary.position, 60);
+        let boundary = Boundary::new(&RECT, Axis::Y, Direction::Decreasing, 0.3);
+        assert_eq!(boundary.position, 55);
+    }
+
+    #[test]
+    fn ratio_at_inverts_the_boundary_position() {
+        for &direction in &[Direction::Increasing, Direction::Decreasing] {
+            for &axis in &[Axis::X, Axis::Y] {
+                let boundary = Boundary::new(&RECT, axis, direction, 0.4);
+                let point = match axis {
+                    Axis::X => Position::new(boundary.position, 30),
+                    Axis::Y => Position::new(30, boundary.position),
+                };
+                let ratio = boundary.ratio_at(point);
+                let size = axis.extract_size(&RECT) as f64;
+                assert!(
+                    (ratio - 0.4).abs() <= 1.0 / size,
+                    "{:?} {:?}",
+                    axis,
+                    direction
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn ratio_at_extends_beyond_the_rect() {
+        let boundary = Boundary::new(&RECT, Axis::X, Direction::Increasing, 0.5);
+        assert!(boundary.ratio_at(Position::new(0, 30)) < 0.0);
+        assert!(boundary.ratio_at(Position::new(200, 30)) > 1.0);
+    }
+
+    #[test]
+    fn boundary_is_near_only_along_its_length() {
+        let boundary = Boundary::new(&RECT, Axis::X, Direction::Increasing, 0.5);
+        assert!(boundary.is_near(Position::new(62, 30), 2));
+        assert!(!boundary.is_near(Position::new(63, 30), 2));
+        assert!(!boundary.is_near(Position::new(60, 70), 2));
+    }
+}
@@ -1,5 +1,9 @@
 use crate::{
-    artist::Artist, commands::Commands, connection::*, layout::*, window_data::WindowData,
+    artist::Artist,
+    commands::{Argument, ArgumentType, CommandDescriptor, CommandResult, Commands, Parameter},
+    connection::*,
+    layout::*,
+    window_data::WindowData,
 };
 
 pub fn new(
@@ -12,17 +16,37 @@ pub fn new(
         width,
         color,
         focus_color,
+        is_smart: false,
+        is_enabled: true,
         child,
     })
 }
 
+// Omits the border of tiled windows when there is only a single tile to show
+pub fn new_smart(
+    width: u8,
+    color: (u8, u8, u8),
+    focus_color: (u8, u8, u8),
+    child: Box<dyn Layout>,
+) -> Box<AddBorder> {
+    let mut layout = new(width, color, focus_color, child);
+    layout.is_smart = true;
+    layout
+}
+
 pub struct AddBorder {
     width: u8,
     color: (u8, u8, u8),
     focus_color: (u8, u8, u8),
+    is_smart: bool,
+    is_enabled: bool, // from the settings
     child: Box<dyn Layout>,
 }
 
+impl AddBorder {
+    const MAXIMUM_WIDTH: u8 = 20;
+}
+
 impl Layout for AddBorder {
     fn layout(
         &self,
@@ -34,10 +58,15 @@ impl Layout for AddBorder {
             .unwrap()
             .focus();
 
+        let is_single_tile = self.is_smart && self.child.has_single_tile(rect, &windows);
         let (mut new_windows, artists) = self.child.layout(rect, windows);
 
         for window in new_windows.iter_mut() {
-            window.border_width = self.width;
+            window.border_width = if !self.is_enabled || (is_single_tile && !window.is_floating) {
+                0
+            } else {
+                self.width
+            };
             if window.window() == focused_window {
                 window.border_color = self.focus_color;
             } else {
@@ -47,14 +76,68 @@ impl Layout for AddBorder {
 
         (new_windows, artists)
     }
+
+    // The width is a setting rather than arrangement, so undo leaves it alone
+    fn get_state(&self) -> LayoutState {
+        self.child.get_state()
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        self.child.set_state(state);
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.child.set_focused_window(window);
+    }
+
+    fn set_settings(&mut self, settings: LayoutSettings) {
+        self.is_enabled = settings.are_borders_enabled;
+        self.child.set_settings(settings);
+    }
+
+    fn get_boundaries(&self, rect: &Bounds, windows: &[WindowData]) -> Vec<Boundary> {
+        self.child.get_boundaries(rect, windows)
+    }
+
+    fn has_single_tile(&self, rect: &Bounds, windows: &[WindowData]) -> bool {
+        self.child.has_single_tile(rect, windows)
+    }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.child.is_weighted(window)
+    }
 }
 
 impl Commands for AddBorder {
-    fn get_commands(&self) -> Vec<String> {
-        self.child.get_commands()
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands = self.child.get_commands();
+        if self.is_enabled {
+            commands.push(
+                CommandDescriptor::new("set_border_width:", "Set the width of window borders")
+                    .with_parameter(Parameter::new("width", ArgumentType::Number)),
+            );
+        }
+        commands
     }
 
-    fn execute_command(&mut self, command: &str, args: &[&str]) -> bool {
-        self.child.execute_command(command, args)
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
+        match command {
+            "set_border_width:" if self.is_enabled => match args {
+                [Argument::Number(width)]
+                    if *width >= 0 && *width <= Self::MAXIMUM_WIDTH as i64 =>
+                {
+                    let is_changed = *width as u8 != self.width;
+                    self.width = *width as u8;
+                    CommandResult::changed_if(is_changed)
+                }
+                [Argument::Number(_)] => CommandResult::Error(format!(
+                    "Border width must be between 0 and {}",
+                    Self::MAXIMUM_WIDTH
+                )),
+                _ => CommandResult::invalid_arguments(command),
+            },
+            "set_border_width:" => CommandResult::Unchanged,
+            _ => self.child.execute_command(command, args),
+        }
     }
 }
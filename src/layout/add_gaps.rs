@@ -1,51 +1,237 @@
-use crate::{artist::Artist, commands::Commands, layout::*, window_data::WindowData};
+use crate::{
+    artist::Artist,
+    commands::{Argument, ArgumentType, CommandDescriptor, CommandResult, Commands, Parameter},
+    layout::*,
+    window_data::WindowData,
+};
 
 pub fn new(screen_gap: u16, window_gap: u16, child: Box<dyn Layout>) -> Box<AddGaps> {
     Box::new(AddGaps {
         screen_gap,
         window_gap,
+        is_enabled: true,
+        is_smart: false,
         child,
     })
 }
 
+// Omits the gaps when there is only a single tile to show
+pub fn new_smart(screen_gap: u16, window_gap: u16, child: Box<dyn Layout>) -> Box<AddGaps> {
+    let mut layout = new(screen_gap, window_gap, child);
+    layout.is_smart = true;
+    layout
+}
+
 pub struct AddGaps {
     screen_gap: u16,
     window_gap: u16,
+    is_enabled: bool, // from the settings
+    is_smart: bool,
     child: Box<dyn Layout>,
 }
 
+impl AddGaps {
+    const GAP_STEP: u16 = 1;
+    const MAXIMUM_GAP: u16 = 50;
+
+    fn gaps(&self) -> (u16, u16) {
+        if self.is_enabled {
+            (self.screen_gap, self.window_gap)
+        } else {
+            (0, 0)
+        }
+    }
+
+    fn inner_rect(&self, rect: &Bounds) -> Bounds {
+        let (screen_gap, _) = self.gaps();
+        Bounds::new(
+            rect.origin.x + screen_gap as i16,
+            rect.origin.y + screen_gap as i16,
+            rect.size.width.saturating_sub(2 * screen_gap),
+            rect.size.height.saturating_sub(2 * screen_gap),
+        )
+    }
+
+    fn set_gaps(&mut self, screen_gap: u16, window_gap: u16) -> CommandResult {
+        let is_changed = (screen_gap, window_gap) != (self.screen_gap, self.window_gap);
+        self.screen_gap = screen_gap;
+        self.window_gap = window_gap;
+        CommandResult::changed_if(is_changed)
+    }
+
+    fn add_window_gaps(&self, mut windows: Vec<WindowData>, window_gap: u16) -> Vec<WindowData> {
+        for window in windows.iter_mut() {
+            window.bounds.origin.x += window_gap as i16;
+            window.bounds.origin.y += window_gap as i16;
+            window.bounds.size.width = window.bounds.size.width.saturating_sub(2 * window_gap);
+            window.bounds.size.height = window.bounds.size.height.saturating_sub(2 * window_gap);
+        }
+        windows
+    }
+}
+
 impl Layout for AddGaps {
     fn layout(
         &self,
         rect: &Bounds,
         windows: Vec<WindowData>,
     ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
-        let r = Bounds::new(
-            rect.origin.x + self.screen_gap as i16,
-            rect.origin.y + self.screen_gap as i16,
-            rect.size.width - 2 * self.screen_gap,
-            rect.size.height - 2 * self.screen_gap,
-        );
+        if self.is_smart && self.child.has_single_tile(rect, &windows) {
+            return self.child.layout(rect, windows);
+        }
 
-        let (mut new_windows, artists) = self.child.layout(&r, windows);
+        let (_, window_gap) = self.gaps();
+        let (new_windows, artists) = self.child.layout(&self.inner_rect(rect), windows);
+        (self.add_window_gaps(new_windows, window_gap), artists)
+    }
 
-        for window in new_windows.iter_mut() {
-            window.bounds.origin.x += self.window_gap as i16;
-            window.bounds.origin.y += self.window_gap as i16;
-            window.bounds.size.width -= 2 * self.window_gap;
-            window.bounds.size.height -= 2 * self.window_gap;
-        }
+    // Gaps are settings rather than arrangement, so undo leaves them alone
+    fn get_state(&self) -> LayoutState {
+        self.child.get_state()
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        self.child.set_state(state);
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.child.set_focused_window(window);
+    }
+
+    fn set_settings(&mut self, settings: LayoutSettings) {
+        self.is_enabled = settings.are_gaps_enabled;
+        self.child.set_settings(settings);
+    }
+
+    fn get_boundaries(&self, rect: &Bounds, windows: &[WindowData]) -> Vec<Boundary> {
+        self.child.get_boundaries(&self.inner_rect(rect), windows)
+    }
 
-        (new_windows, artists)
+    fn has_single_tile(&self, rect: &Bounds, windows: &[WindowData]) -> bool {
+        self.child.has_single_tile(&self.inner_rect(rect), windows)
+    }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.child.is_weighted(window)
     }
 }
 
 impl Commands for AddGaps {
-    fn get_commands(&self) -> Vec<String> {
-        self.child.get_commands()
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands = self.child.get_commands();
+        if self.is_enabled {
+            if self.screen_gap < Self::MAXIMUM_GAP || self.window_gap < Self::MAXIMUM_GAP {
+                commands.push(CommandDescriptor::new(
+                    "increase_gaps",
+                    "Increase the gaps around and between windows",
+                ));
+            }
+            if self.screen_gap > 0 || self.window_gap > 0 {
+                commands.push(CommandDescriptor::new(
+                    "decrease_gaps",
+                    "Decrease the gaps around and between windows",
+                ));
+            }
+            commands.push(
+                CommandDescriptor::new("set_gaps:", "Set the gaps around and between windows")
+                    .with_parameter(Parameter::new("screen_gap", ArgumentType::Number))
+                    .with_parameter(Parameter::new("window_gap", ArgumentType::Number)),
+            );
+        }
+        commands
+    }
+
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
+        match command {
+            "increase_gaps" if self.is_enabled => self.set_gaps(
+                (self.screen_gap + Self::GAP_STEP).min(Self::MAXIMUM_GAP),
+                (self.window_gap + Self::GAP_STEP).min(Self::MAXIMUM_GAP),
+            ),
+            "decrease_gaps" if self.is_enabled => self.set_gaps(
+                self.screen_gap.saturating_sub(Self::GAP_STEP),
+                self.window_gap.saturating_sub(Self::GAP_STEP),
+            ),
+            "increase_gaps" | "decrease_gaps" => CommandResult::Unchanged,
+            "set_gaps:" if self.is_enabled => match args {
+                [Argument::Number(screen_gap), Argument::Number(window_gap)]
+                    if [screen_gap, window_gap]
+                        .iter()
+                        .all(|gap| **gap >= 0 && **gap <= Self::MAXIMUM_GAP as i64) =>
+                {
+                    self.set_gaps(*screen_gap as u16, *window_gap as u16)
+                }
+                [Argument::Number(_), Argument::Number(_)] => CommandResult::Error(format!(
+                    "Gaps must be between 0 and {}",
+                    Self::MAXIMUM_GAP
+                )),
+                _ => CommandResult::invalid_arguments(command),
+            },
+            "set_gaps:" => CommandResult::Unchanged,
+            _ => self.child.execute_command(command, args),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(layout: &AddGaps, number_of_windows: u32) -> Vec<Bounds> {
+        let windows = (1..=number_of_windows).map(WindowData::new).collect();
+        let (new_windows, _) = layout.layout(&Bounds::new(0, 0, 800, 600), windows);
+        new_windows.iter().map(|w| w.bounds).collect()
+    }
+
+    fn linear() -> Box<dyn Layout> {
+        linear_layout::new(Direction::Increasing, Axis::X)
+    }
+
+    #[test]
+    fn gaps_surround_and_separate_windows() {
+        assert_eq!(
+            bounds(&new(5, 5, linear()), 2),
+            vec![
+                Bounds::new(10, 10, 385, 580),
+                Bounds::new(405, 10, 385, 580)
+            ]
+        );
     }
 
-    fn execute_command(&mut self, command: &str, args: &[&str]) -> bool {
-        self.child.execute_command(command, args)
+    #[test]
+    fn smart_gaps_are_omitted_for_a_single_tile() {
+        let layout = new_smart(5, 5, linear());
+        assert_eq!(bounds(&layout, 1), vec![Bounds::new(0, 0, 800, 600)]);
+        assert_eq!(bounds(&layout, 2), bounds(&new(5, 5, linear()), 2));
+    }
+
+    #[test]
+    fn screen_and_window_gaps_are_set_separately() {
+        let mut layout = new(5, 5, linear());
+        let state = layout.get_state();
+        assert!(matches!(
+            layout.execute_command("set_gaps:", &[Argument::Number(0), Argument::Number(10)]),
+            CommandResult::Changed
+        ));
+        assert_eq!(
+            bounds(&layout, 2),
+            vec![
+                Bounds::new(10, 10, 380, 580),
+                Bounds::new(410, 10, 380, 580)
+            ]
+        );
+        assert_eq!(layout.get_state(), state);
+    }
+
+    #[test]
+    fn disabled_gaps_are_omitted() {
+        let mut layout = new(5, 5, linear());
+        layout.set_settings(LayoutSettings {
+            are_gaps_enabled: false,
+            are_borders_enabled: true,
+        });
+        assert_eq!(
+            bounds(&layout, 2),
+            vec![Bounds::new(0, 0, 400, 600), Bounds::new(400, 0, 400, 600)]
+        );
     }
 }
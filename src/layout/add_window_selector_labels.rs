@@ -1,8 +1,14 @@
 use crate::{
-    artist::Artist, commands::Commands, connection::*, layout::*, window_data::WindowData,
+    artist::Artist,
+    commands::{Argument, CommandDescriptor, CommandResult, Commands},
+    connection::*,
+    layout::*,
+    window_data::WindowData,
 };
 use std::collections::HashMap;
 
+pub const SELECTOR_LABELS: &str = "ASDFGHJKLQWERTYUIOPZXCVBNM1234567890";
+
 pub fn new(child: Box<dyn Layout>) -> Box<AddWindowSelectorLabels> {
     Box::new(AddWindowSelectorLabels {
         is_enabled: false,
@@ -29,7 +35,7 @@ impl Layout for AddWindowSelectorLabels {
 
         // TODO: allow choice of preserve or refresh label assignment policy
 
-        let selector_chars = "ASDFGHJKLQWERTYUIOPZXCVBNM1234567890".chars();
+        let selector_chars = SELECTOR_LABELS.chars();
         let mut selector_artists: HashMap<xcb::Window, WindowSelectorArtist> = HashMap::new();
         // check window is mapped, and is not e.g. the help window
         for (w, c) in new_windows.iter_mut().zip(selector_chars) {
@@ -47,28 +53,62 @@ impl Layout for AddWindowSelectorLabels {
 
         (new_windows, artists)
     }
+
+    fn get_state(&self) -> LayoutState {
+        self.child.get_state()
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        self.child.set_state(state);
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.child.set_focused_window(window);
+    }
+
+    fn set_settings(&mut self, settings: LayoutSettings) {
+        self.child.set_settings(settings);
+    }
+
+    fn get_boundaries(&self, rect: &Bounds, windows: &[WindowData]) -> Vec<Boundary> {
+        self.child.get_boundaries(rect, windows)
+    }
+
+    fn has_single_tile(&self, rect: &Bounds, windows: &[WindowData]) -> bool {
+        self.child.has_single_tile(rect, windows)
+    }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.child.is_weighted(window)
+    }
 }
 
 impl Commands for AddWindowSelectorLabels {
-    fn get_commands(&self) -> Vec<String> {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
         let mut commands = self.child.get_commands();
         if self.is_enabled {
-            commands.push("hide_window_selector_labels".to_owned());
+            commands.push(CommandDescriptor::new(
+                "hide_window_selector_labels",
+                "Hide the window selector labels",
+            ));
         } else {
-            commands.push("show_window_selector_labels".to_owned());
+            commands.push(CommandDescriptor::new(
+                "show_window_selector_labels",
+                "Show the window selector labels",
+            ));
         }
         commands
     }
 
-    fn execute_command(&mut self, command: &str, args: &[&str]) -> bool {
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
         match command {
             "show_window_selector_labels" => {
                 self.is_enabled = true;
-                true
+                CommandResult::Changed
             }
             "hide_window_selector_labels" => {
                 self.is_enabled = false;
-                true
+                CommandResult::Changed
             }
             _ => self.child.execute_command(command, args),
         }
@@ -0,0 +1,131 @@
+use crate::{
+    artist::Artist,
+    commands::{Argument, CommandDescriptor, CommandResult, Commands},
+    layout::*,
+    window_data::WindowData,
+};
+use std::cell::{Cell, RefCell};
+
+pub fn new(child: Box<dyn Layout>) -> Box<AddZoom> {
+    Box::new(AddZoom {
+        zoomed_window: Cell::new(None),
+        focused_window: None,
+        windows: Default::default(),
+        child,
+    })
+}
+
+// A zoomed window fills the whole rect above the others, which keep the bounds the child
+// gives them. Zooming is transient so it is not part of the state, and it ends as soon as
+// the focus moves to another window.
+pub struct AddZoom {
+    zoomed_window: Cell<Option<xcb::Window>>,
+    focused_window: Option<xcb::Window>,
+    windows: RefCell<Vec<xcb::Window>>, // as of the most recent layout
+    child: Box<dyn Layout>,
+}
+
+impl AddZoom {
+    fn focused_window_in_layout(&self) -> Option<xcb::Window> {
+        self.focused_window
+            .filter(|window| self.windows.borrow().contains(window))
+    }
+}
+
+impl Layout for AddZoom {
+    fn layout(
+        &self,
+        rect: &Bounds,
+        windows: Vec<WindowData>,
+    ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        *self.windows.borrow_mut() = windows.iter().map(|w| w.window()).collect();
+        if self.zoomed_window.get() != self.focused_window_in_layout() {
+            self.zoomed_window.set(None);
+        }
+
+        let (mut new_windows, artists) = self.child.layout(rect, windows);
+        match self.zoomed_window.get() {
+            Some(zoomed_window) => {
+                let mut ordered_windows = new_windows.iter_mut().collect::<Vec<_>>();
+                ordered_windows
+                    .sort_by_key(|w| (w.window() == zoomed_window, w.order.unwrap_or(0)));
+                for (order, window) in ordered_windows.into_iter().enumerate() {
+                    window.order = Some(order as i16);
+                    if window.window() == zoomed_window {
+                        window.bounds = *rect;
+                    }
+                }
+                // The child's decorations belong to the windows hidden behind
+                (new_windows, Default::default())
+            }
+            None => (new_windows, artists),
+        }
+    }
+
+    fn get_state(&self) -> LayoutState {
+        self.child.get_state()
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        self.child.set_state(state);
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.focused_window = window;
+        self.child.set_focused_window(window);
+    }
+
+    fn set_settings(&mut self, settings: LayoutSettings) {
+        self.child.set_settings(settings);
+    }
+
+    fn get_boundaries(&self, rect: &Bounds, windows: &[WindowData]) -> Vec<Boundary> {
+        if self.zoomed_window.get().is_some() {
+            vec![]
+        } else {
+            self.child.get_boundaries(rect, windows)
+        }
+    }
+
+    fn has_single_tile(&self, rect: &Bounds, windows: &[WindowData]) -> bool {
+        self.child.has_single_tile(rect, windows)
+    }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.child.is_weighted(window)
+    }
+}
+
+impl Commands for AddZoom {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands = self.child.get_commands();
+        if self.zoomed_window.get().is_some() {
+            commands.push(CommandDescriptor::new(
+                "toggle_zoom",
+                "Return the zoomed window to its place in the layout",
+            ));
+        } else if self.windows.borrow().len() > 1 && self.focused_window_in_layout().is_some() {
+            commands.push(CommandDescriptor::new(
+                "toggle_zoom",
+                "Let the focused window fill the screen for now",
+            ));
+        }
+        commands
+    }
+
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
+        match command {
+            "toggle_zoom" => {
+                if self.zoomed_window.get().is_some() {
+                    self.zoomed_window.set(None);
+                    CommandResult::Changed
+                } else {
+                    let focused_window = self.focused_window_in_layout();
+                    self.zoomed_window.set(focused_window);
+                    CommandResult::changed_if(focused_window.is_some())
+                }
+            }
+            _ => self.child.execute_command(command, args),
+        }
+    }
+}
@@ -0,0 +1,790 @@
+use crate::{
+    artist::Artist,
+    commands::{Argument, ArgumentType, CommandDescriptor, CommandResult, Commands, Parameter},
+    connection::*,
+    layout::*,
+    window_data::WindowData,
+};
+use std::cell::{Cell, RefCell};
+
+pub fn new() -> Box<BspLayout> {
+    Box::new(BspLayout {
+        root: RefCell::new(None),
+        preselection: Cell::new(None),
+        focused_window: None,
+    })
+}
+
+// The first child of a split is always the left / top one
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(xcb::Window),
+    Split {
+        axis: Axis,
+        ratio: f64,
+        children: Box<(Node, Node)>,
+    },
+}
+
+impl Node {
+    fn is_leaf(&self, window: xcb::Window) -> bool {
+        match self {
+            Node::Leaf(w) => *w == window,
+            Node::Split { .. } => false,
+        }
+    }
+
+    fn contains(&self, window: xcb::Window) -> bool {
+        match self {
+            Node::Leaf(w) => *w == window,
+            Node::Split { children, .. } => {
+                children.0.contains(window) || children.1.contains(window)
+            }
+        }
+    }
+
+    fn last_window(&self) -> xcb::Window {
+        match self {
+            Node::Leaf(w) => *w,
+            Node::Split { children, .. } => children.1.last_window(),
+        }
+    }
+
+    fn number_of_windows(&self) -> usize {
+        match self {
+            Node::Leaf(_) => 1,
+            Node::Split { children, .. } => {
+                children.0.number_of_windows() + children.1.number_of_windows()
+            }
+        }
+    }
+
+    // Removing a leaf replaces its parent split with the sibling
+    fn retain(self, windows: &[xcb::Window]) -> Option<Node> {
+        match self {
+            Node::Leaf(w) if windows.contains(&w) => Some(self),
+            Node::Leaf(_) => None,
+            Node::Split {
+                axis,
+                ratio,
+                children,
+            } => {
+                let (a, b) = *children;
+                match (a.retain(windows), b.retain(windows)) {
+                    (Some(a), Some(b)) => Some(Node::Split {
+                        axis,
+                        ratio,
+                        children: Box::new((a, b)),
+                    }),
+                    (Some(node), None) | (None, Some(node)) => Some(node),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+
+    // Splits the target's tile in half, along its longest axis unless a split is given
+    fn insert(
+        &mut self,
+        rect: &Bounds,
+        target: xcb::Window,
+        window: xcb::Window,
+        split: Option<(Axis, Direction)>,
+    ) -> bool {
+        match self {
+            Node::Leaf(w) if *w == target => {
+                let (axis, direction) =
+                    split.unwrap_or((rect.size.largest_axis(), Direction::Increasing));
+                let children = match direction {
+                    Direction::Increasing => (Node::Leaf(target), Node::Leaf(window)),
+                    Direction::Decreasing => (Node::Leaf(window), Node::Leaf(target)),
+                };
+                *self = Node::Split {
+                    axis,
+                    ratio: 0.5,
+                    children: Box::new(children),
+                };
+                true
+            }
+            Node::Leaf(_) => false,
+            Node::Split {
+                axis,
+                ratio,
+                children,
+            } => {
+                let (rect_1, rect_2) = split_rect(rect, *axis, Direction::Increasing, *ratio);
+                children.0.insert(&rect_1, target, window, split)
+                    || children.1.insert(&rect_2, target, window, split)
+            }
+        }
+    }
+
+    fn place(&self, rect: &Bounds, placements: &mut Vec<(xcb::Window, Bounds)>) {
+        match self {
+            Node::Leaf(w) => placements.push((*w, *rect)),
+            Node::Split {
+                axis,
+                ratio,
+                children,
+            } => {
+                let (rect_1, rect_2) = split_rect(rect, *axis, Direction::Increasing, *ratio);
+                children.0.place(&rect_1, placements);
+                children.1.place(&rect_2, placements);
+            }
+        }
+    }
+
+    // The path of child indices to the innermost split containing the window, which is empty
+    // if there is none
+    fn path_to_split_containing(&self, window: xcb::Window) -> Vec<usize> {
+        match self {
+            Node::Split { children, .. }
+                if !children.0.is_leaf(window) && !children.1.is_leaf(window) =>
+            {
+                let (index, child) = if children.0.contains(window) {
+                    (0, &children.0)
+                } else if children.1.contains(window) {
+                    (1, &children.1)
+                } else {
+                    return vec![];
+                };
+                let mut path = vec![index];
+                path.extend(child.path_to_split_containing(window));
+                path
+            }
+            _ => vec![],
+        }
+    }
+
+    fn node_at(&self, path: &[usize]) -> Option<&Node> {
+        match (self, path) {
+            (node, []) => Some(node),
+            (Node::Split { children, .. }, [0, rest @ ..]) => children.0.node_at(rest),
+            (Node::Split { children, .. }, [1, rest @ ..]) => children.1.node_at(rest),
+            _ => None,
+        }
+    }
+
+    fn node_at_mut(&mut self, path: &[usize]) -> Option<&mut Node> {
+        match (self, path) {
+            (node, []) => Some(node),
+            (Node::Split { children, .. }, [0, rest @ ..]) => children.0.node_at_mut(rest),
+            (Node::Split { children, .. }, [1, rest @ ..]) => children.1.node_at_mut(rest),
+            _ => None,
+        }
+    }
+
+    // The innermost split containing the window, or the node itself if there is none
+    fn split_containing(&mut self, window: xcb::Window) -> &mut Node {
+        let path = self.path_to_split_containing(window);
+        self.node_at_mut(&path).unwrap()
+    }
+
+    fn ratio(&self) -> Option<f64> {
+        match self {
+            Node::Leaf(_) => None,
+            Node::Split { ratio, .. } => Some(*ratio),
+        }
+    }
+
+    fn set_ratio(&mut self, new_ratio: f64) -> CommandResult {
+        match self {
+            Node::Leaf(_) => CommandResult::Unchanged,
+            Node::Split { ratio, .. } => {
+                let new_ratio = new_ratio.clamp(BspLayout::MINIMUM_RATIO, BspLayout::MAXIMUM_RATIO);
+                let is_changed = (new_ratio - *ratio).abs() > f64::EPSILON;
+                *ratio = new_ratio;
+                CommandResult::changed_if(is_changed)
+            }
+        }
+    }
+
+    // Each boundary is prefixed with the path to its split, e.g. "1/0/"
+    fn boundaries(&self, rect: &Bounds) -> Vec<Boundary> {
+        match self {
+            Node::Leaf(_) => vec![],
+            Node::Split {
+                axis,
+                ratio,
+                children,
+            } => {
+                let (rect_1, rect_2) = split_rect(rect, *axis, Direction::Increasing, *ratio);
+                let mut boundaries =
+                    vec![Boundary::new(rect, *axis, Direction::Increasing, *ratio)];
+                boundaries.extend(combine_child_boundaries(vec![
+                    children.0.boundaries(&rect_1),
+                    children.1.boundaries(&rect_2),
+                ]));
+                boundaries
+            }
+        }
+    }
+
+    fn rotate(&mut self, clockwise: bool) {
+        if let Node::Split {
+            axis,
+            ratio,
+            children,
+        } = self
+        {
+            // Clockwise, left becomes top and top becomes right
+            let swap = (*axis == Axis::Y) == clockwise;
+            if swap {
+                std::mem::swap(&mut children.0, &mut children.1);
+                *ratio = 1.0 - *ratio;
+            }
+            *axis = axis.orthogonal();
+            children.0.rotate(clockwise);
+            children.1.rotate(clockwise);
+        }
+    }
+
+    fn flip(&mut self, flip_axis: Axis) {
+        if let Node::Split {
+            axis,
+            ratio,
+            children,
+        } = self
+        {
+            if *axis == flip_axis {
+                std::mem::swap(&mut children.0, &mut children.1);
+                *ratio = 1.0 - *ratio;
+            }
+            children.0.flip(flip_axis);
+            children.1.flip(flip_axis);
+        }
+    }
+
+    // Gives every window the same area, returns the number of windows
+    fn balance(&mut self) -> usize {
+        match self {
+            Node::Leaf(_) => 1,
+            Node::Split {
+                ratio, children, ..
+            } => {
+                let a = children.0.balance();
+                let b = children.1.balance();
+                *ratio = a as f64 / (a + b) as f64;
+                a + b
+            }
+        }
+    }
+
+    fn to_state(&self) -> LayoutState {
+        match self {
+            Node::Leaf(w) => LayoutState::new(vec![*w as f64], vec![]),
+            Node::Split {
+                axis,
+                ratio,
+                children,
+            } => LayoutState::new(
+                vec![
+                    match axis {
+                        Axis::X => 0.0,
+                        Axis::Y => 1.0,
+                    },
+                    *ratio,
+                ],
+                vec![children.0.to_state(), children.1.to_state()],
+            ),
+        }
+    }
+
+    fn from_state(state: &LayoutState) -> Option<Node> {
+        match (state.values.as_slice(), state.children.as_slice()) {
+            ([window], []) => Some(Node::Leaf(*window as xcb::Window)),
+            ([axis, ratio], [a, b]) => Some(Node::Split {
+                axis: if *axis < 0.5 { Axis::X } else { Axis::Y },
+                ratio: *ratio,
+                children: Box::new((Node::from_state(a)?, Node::from_state(b)?)),
+            }),
+            _ => None,
+        }
+    }
+}
+
+// One side of the outline around the area the next window will take, so the target window
+// stays visible
+struct PreselectionArtist {
+    bounds: Bounds,
+}
+
+impl PreselectionArtist {
+    const OUTLINE_WIDTH: u16 = 2;
+
+    fn outline(rect: &Bounds) -> Vec<Box<dyn Artist>> {
+        let width = Self::OUTLINE_WIDTH
+            .min(rect.size.width / 2)
+            .min(rect.size.height / 2)
+            .max(1);
+        [
+            Bounds::new(rect.origin.x, rect.origin.y, rect.size.width, width),
+            Bounds::new(
+                rect.origin.x,
+                rect.max_y() - width as i16,
+                rect.size.width,
+                width,
+            ),
+            Bounds::new(rect.origin.x, rect.origin.y, width, rect.size.height),
+            Bounds::new(
+                rect.max_x() - width as i16,
+                rect.origin.y,
+                width,
+                rect.size.height,
+            ),
+        ]
+        .iter()
+        .map(|bounds| Box::new(PreselectionArtist { bounds: *bounds }) as Box<dyn Artist>)
+        .collect()
+    }
+}
+
+impl Artist for PreselectionArtist {
+    fn calculate_bounds(&self, _window: xcb::Window) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn draw(&self, window: xcb::Window) {
+        if let Ok(geometry) = xcb::get_geometry(connection(), window).get_reply() {
+            if let Ok(surface) = get_cairo_surface(window) {
+                let context = cairo::Context::new(&surface);
+                context.set_source_rgb(0.125, 0.375, 0.5);
+                context.rectangle(0.0, 0.0, geometry.width() as f64, geometry.height() as f64);
+                context.fill();
+            }
+        }
+    }
+}
+
+// The tree is kept in sync with the windows as they are laid out, which only has shared
+// access to the layout
+pub struct BspLayout {
+    root: RefCell<Option<Node>>,
+    preselection: Cell<Option<(xcb::Window, Axis, Direction)>>,
+    focused_window: Option<xcb::Window>,
+}
+
+impl BspLayout {
+    const MINIMUM_RATIO: f64 = 0.1;
+    const MAXIMUM_RATIO: f64 = 0.9;
+    const RATIO_STEP: f64 = 0.05;
+
+    fn preselect(&mut self, axis: Axis, direction: Direction) -> CommandResult {
+        match (self.root.get_mut(), self.focused_window) {
+            (Some(root), Some(focused_window)) if root.contains(focused_window) => {
+                self.preselection
+                    .set(Some((focused_window, axis, direction)));
+                CommandResult::Changed
+            }
+            _ => CommandResult::error("No window focused"),
+        }
+    }
+}
+
+impl Layout for BspLayout {
+    fn layout(
+        &self,
+        rect: &Bounds,
+        windows: Vec<WindowData>,
+    ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        let window_ids = windows.iter().map(|w| w.window()).collect::<Vec<_>>();
+        let mut root = self.root.borrow_mut();
+        *root = root.take().and_then(|node| node.retain(&window_ids));
+
+        if windows.is_empty() {
+            self.preselection.set(None);
+            return Default::default();
+        }
+
+        let mut preselection = self
+            .preselection
+            .get()
+            .filter(|(w, _, _)| window_ids.contains(w));
+        let mut last_inserted = None;
+        for &window in window_ids.iter() {
+            match &mut *root {
+                Some(node) if node.contains(window) => {}
+                Some(node) => {
+                    let (target, split) = match preselection.take() {
+                        Some((target, axis, direction)) => (target, Some((axis, direction))),
+                        None => (
+                            last_inserted
+                                .or(self.focused_window.filter(|w| node.contains(*w)))
+                                .unwrap_or_else(|| node.last_window()),
+                            None,
+                        ),
+                    };
+                    node.insert(rect, target, window, split);
+                    last_inserted = Some(window);
+                }
+                None => {
+                    *root = Some(Node::Leaf(window));
+                    last_inserted = Some(window);
+                }
+            }
+        }
+        self.preselection.set(preselection);
+
+        let node = root.as_ref().unwrap();
+        let mut placements = Vec::with_capacity(windows.len());
+        node.place(rect, &mut placements);
+
+        let mut new_windows = windows;
+        for window in new_windows.iter_mut() {
+            if let Some((_, bounds)) = placements.iter().find(|(w, _)| *w == window.window()) {
+                window.bounds = *bounds;
+            }
+        }
+        clear_window_order(&mut new_windows);
+
+        let mut artists: Vec<Box<dyn Artist>> = Vec::new();
+        if let Some((target, axis, direction)) = preselection {
+            if let Some((_, bounds)) = placements.iter().find(|(w, _)| *w == target) {
+                let (rect_1, rect_2) = split_rect(bounds, axis, Direction::Increasing, 0.5);
+                artists.extend(PreselectionArtist::outline(match direction {
+                    Direction::Increasing => &rect_2,
+                    Direction::Decreasing => &rect_1,
+                }));
+            }
+        }
+
+        (new_windows, artists)
+    }
+
+    fn get_state(&self) -> LayoutState {
+        self.root
+            .borrow()
+            .as_ref()
+            .map(Node::to_state)
+            .unwrap_or_default()
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        *self.root.get_mut() = Node::from_state(state);
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.focused_window = window;
+    }
+
+    // As of the most recent layout, which is done in the same rect
+    fn get_boundaries(&self, rect: &Bounds, _windows: &[WindowData]) -> Vec<Boundary> {
+        self.root
+            .borrow()
+            .as_ref()
+            .map_or_else(Vec::new, |root| root.boundaries(rect))
+    }
+}
+
+impl Commands for BspLayout {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let number_of_windows = self
+            .root
+            .borrow()
+            .as_ref()
+            .map_or(0, Node::number_of_windows);
+
+        let mut commands = Vec::new();
+        if number_of_windows > 1 {
+            commands.push(CommandDescriptor::new(
+                "rotate_clockwise",
+                "Rotate the split containing the focused window clockwise",
+            ));
+            commands.push(CommandDescriptor::new(
+                "rotate_counterclockwise",
+                "Rotate the split containing the focused window counterclockwise",
+            ));
+            commands.push(CommandDescriptor::new(
+                "flip_horizontal",
+                "Mirror the split containing the focused window left to right",
+            ));
+            commands.push(CommandDescriptor::new(
+                "flip_vertical",
+                "Mirror the split containing the focused window top to bottom",
+            ));
+            commands.push(CommandDescriptor::new(
+                "balance",
+                "Give every window the same area",
+            ));
+
+            let ratio = self.root.borrow().as_ref().and_then(|root| {
+                let path = root.path_to_split_containing(self.focused_window.unwrap_or(xcb::NONE));
+                root.node_at(&path).and_then(Node::ratio)
+            });
+            if ratio.is_some_and(|ratio| ratio < Self::MAXIMUM_RATIO) {
+                commands.push(CommandDescriptor::new(
+                    "increase_ratio",
+                    "Enlarge the left or top side of the split containing the focused window",
+                ));
+            }
+            if ratio.is_some_and(|ratio| ratio > Self::MINIMUM_RATIO) {
+                commands.push(CommandDescriptor::new(
+                    "decrease_ratio",
+                    "Shrink the left or top side of the split containing the focused window",
+                ));
+            }
+            commands.push(
+                CommandDescriptor::new(
+                    "set_ratio:",
+                    "Set the size of the left or top side of the split containing the focused window",
+                )
+                .with_parameter(Parameter::new("thousandths", ArgumentType::Number)),
+            );
+        }
+        if number_of_windows > 0 {
+            commands.push(CommandDescriptor::new(
+                "preselect_left",
+                "Place the next window to the left of the focused window",
+            ));
+            commands.push(CommandDescriptor::new(
+                "preselect_right",
+                "Place the next window to the right of the focused window",
+            ));
+            commands.push(CommandDescriptor::new(
+                "preselect_up",
+                "Place the next window above the focused window",
+            ));
+            commands.push(CommandDescriptor::new(
+                "preselect_down",
+                "Place the next window below the focused window",
+            ));
+        }
+        if self.preselection.get().is_some() {
+            commands.push(CommandDescriptor::new(
+                "cancel_preselection",
+                "Place the next window normally",
+            ));
+        }
+        commands
+    }
+
+    // Commands prefixed with a path, as given by the boundaries, apply to the split at the end of
+    // the path instead of the one containing the focused window
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
+        match command {
+            "preselect_left" => return self.preselect(Axis::X, Direction::Decreasing),
+            "preselect_right" => return self.preselect(Axis::X, Direction::Increasing),
+            "preselect_up" => return self.preselect(Axis::Y, Direction::Decreasing),
+            "preselect_down" => return self.preselect(Axis::Y, Direction::Increasing),
+            "cancel_preselection" => {
+                return CommandResult::changed_if(self.preselection.replace(None).is_some())
+            }
+            _ => {}
+        }
+
+        let mut path = Vec::new();
+        let mut command = command;
+        while let Some(index) = ["0/", "1/"].iter().position(|p| command.starts_with(p)) {
+            path.push(index);
+            command = &command[2..];
+        }
+
+        // Without a focused window the commands apply to the whole tree
+        let focused_window = self.focused_window.unwrap_or(xcb::NONE);
+        let root = match self.root.get_mut() {
+            Some(root) => root,
+            None => {
+                return match command {
+                    "rotate_clockwise"
+                    | "rotate_counterclockwise"
+                    | "flip_horizontal"
+                    | "flip_vertical"
+                    | "balance"
+                    | "increase_ratio"
+                    | "decrease_ratio"
+                    | "set_ratio:" => CommandResult::Unchanged,
+                    _ => CommandResult::unknown_command(command),
+                }
+            }
+        };
+        let node = if !path.is_empty() {
+            match root.node_at_mut(&path) {
+                Some(node) => node,
+                None => return CommandResult::error("No such split"),
+            }
+        } else if command == "balance" {
+            root
+        } else {
+            root.split_containing(focused_window)
+        };
+        match command {
+            "rotate_clockwise" => node.rotate(true),
+            "rotate_counterclockwise" => node.rotate(false),
+            "flip_horizontal" => node.flip(Axis::X),
+            "flip_vertical" => node.flip(Axis::Y),
+            "balance" => {
+                node.balance();
+            }
+            "increase_ratio" | "decrease_ratio" => {
+                let step = if command == "increase_ratio" {
+                    Self::RATIO_STEP
+                } else {
+                    -Self::RATIO_STEP
+                };
+                return match node.ratio() {
+                    Some(ratio) => node.set_ratio(ratio + step),
+                    None => CommandResult::Unchanged,
+                };
+            }
+            "set_ratio:" => {
+                return match args {
+                    [Argument::Number(thousandths)] => node.set_ratio(*thousandths as f64 / 1000.0),
+                    _ => CommandResult::invalid_arguments(command),
+                }
+            }
+            _ => return CommandResult::unknown_command(command),
+        }
+        CommandResult::Changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(axis: Axis, a: Node, b: Node) -> Node {
+        Node::Split {
+            axis,
+            ratio: 0.5,
+            children: Box::new((a, b)),
+        }
+    }
+
+    // 1 | (2 / (3 | 4))
+    fn tree() -> Node {
+        split(
+            Axis::X,
+            Node::Leaf(1),
+            split(
+                Axis::Y,
+                Node::Leaf(2),
+                split(Axis::X, Node::Leaf(3), Node::Leaf(4)),
+            ),
+        )
+    }
+
+    fn windows(node: &Node) -> Vec<xcb::Window> {
+        let mut placements = Vec::new();
+        node.place(&Bounds::new(0, 0, 100, 100), &mut placements);
+        placements.iter().map(|(w, _)| *w).collect()
+    }
+
+    #[test]
+    fn split_containing_finds_innermost_split() {
+        let mut root = tree();
+        assert_eq!(windows(root.split_containing(4)), vec![3, 4]);
+        assert_eq!(windows(root.split_containing(2)), vec![2, 3, 4]);
+        assert_eq!(windows(root.split_containing(1)), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn split_containing_returns_node_without_window() {
+        let mut root = tree();
+        assert_eq!(windows(root.split_containing(5)), vec![1, 2, 3, 4]);
+        let mut leaf = Node::Leaf(1);
+        assert_eq!(windows(leaf.split_containing(1)), vec![1]);
+    }
+
+    fn placements(node: &Node) -> Vec<(xcb::Window, Bounds)> {
+        let mut placements = Vec::new();
+        node.place(&Bounds::new(0, 0, 120, 120), &mut placements);
+        placements
+    }
+
+    #[test]
+    fn retain_replaces_split_with_sibling() {
+        let root = tree().retain(&[1, 3]).unwrap();
+        assert_eq!(
+            placements(&root),
+            vec![
+                (1, Bounds::new(0, 0, 60, 120)),
+                (3, Bounds::new(60, 0, 60, 120))
+            ]
+        );
+        assert!(tree().retain(&[]).is_none());
+    }
+
+    #[test]
+    fn rotate_clockwise_moves_left_to_top_and_top_to_right() {
+        let mut root = split(Axis::X, Node::Leaf(1), Node::Leaf(2));
+        root.rotate(true);
+        assert_eq!(
+            placements(&root),
+            vec![
+                (1, Bounds::new(0, 0, 120, 60)),
+                (2, Bounds::new(0, 60, 120, 60))
+            ]
+        );
+        root.rotate(true);
+        assert_eq!(windows(&root), vec![2, 1]);
+        root.rotate(false);
+        root.rotate(false);
+        assert_eq!(
+            placements(&root),
+            vec![
+                (1, Bounds::new(0, 0, 60, 120)),
+                (2, Bounds::new(60, 0, 60, 120))
+            ]
+        );
+    }
+
+    #[test]
+    fn balance_gives_every_window_the_same_area() {
+        let mut root = tree();
+        assert_eq!(root.balance(), 4);
+        for (window, bounds) in placements(&root) {
+            assert_eq!(
+                bounds.size.width as u32 * bounds.size.height as u32,
+                3600,
+                "window {}",
+                window
+            );
+        }
+    }
+
+    #[test]
+    fn boundaries_are_prefixed_with_the_path_to_their_split() {
+        let mut root = tree();
+        let boundaries = root.boundaries(&Bounds::new(0, 0, 120, 120));
+        assert_eq!(
+            boundaries
+                .iter()
+                .map(|b| (b.command_prefix.as_str(), b.axis, b.position))
+                .collect::<Vec<_>>(),
+            vec![
+                ("", Axis::X, 60),
+                ("1/", Axis::Y, 60),
+                ("1/1/", Axis::X, 90)
+            ]
+        );
+        assert_eq!(root.node_at(&[1, 1]).and_then(Node::ratio), Some(0.5));
+        root.node_at_mut(&[1, 1]).unwrap().set_ratio(0.25);
+        assert_eq!(
+            placements(&root)[2..],
+            [
+                (3, Bounds::new(60, 60, 15, 60)),
+                (4, Bounds::new(75, 60, 45, 60))
+            ]
+        );
+    }
+
+    #[test]
+    fn ratios_are_clamped() {
+        let mut root = tree();
+        assert!(matches!(root.set_ratio(0.0), CommandResult::Changed));
+        assert_eq!(root.ratio(), Some(BspLayout::MINIMUM_RATIO));
+        assert!(matches!(root.set_ratio(0.05), CommandResult::Unchanged));
+        assert!(matches!(
+            Node::Leaf(1).set_ratio(0.3),
+            CommandResult::Unchanged
+        ));
+    }
+
+    #[test]
+    fn state_round_trips() {
+        let mut root = tree();
+        root.balance();
+        let restored = Node::from_state(&root.to_state()).unwrap();
+        assert_eq!(placements(&restored), placements(&root));
+    }
+}
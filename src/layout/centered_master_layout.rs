@@ -0,0 +1,138 @@
+use crate::{
+    artist::Artist,
+    commands::{Argument, CommandDescriptor, CommandResult, Commands},
+    layout::*,
+    window_data::WindowData,
+};
+
+pub fn new(ratio: f64, count: usize) -> Box<CenteredMasterLayout> {
+    Box::new(CenteredMasterLayout { ratio, count })
+}
+
+fn arrange_column(windows: Vec<&mut WindowData>, rect: &Bounds) {
+    if windows.is_empty() {
+        return;
+    }
+    let mut r = *rect;
+    r.size.height = rect.size.height / windows.len() as u16;
+    for window in windows {
+        window.bounds = r;
+        r.origin.y += r.size.height as i16;
+    }
+}
+
+// The main windows share a column in the middle, the others alternate between a column on
+// the right and one on the left. With a single other window there is no left column.
+pub struct CenteredMasterLayout {
+    ratio: f64,
+    count: usize,
+}
+
+impl Layout for CenteredMasterLayout {
+    fn layout(
+        &self,
+        rect: &Bounds,
+        windows: Vec<WindowData>,
+    ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        if windows.is_empty() {
+            return Default::default();
+        }
+
+        let mut new_windows = windows;
+        let number_of_main_windows = new_windows.len().min(self.count);
+        let (main_windows, other_windows) = new_windows.split_at_mut(number_of_main_windows);
+
+        let main_width = (rect.size.width as f64 * self.ratio).floor() as u16;
+        let number_of_other_windows = other_windows.len();
+        let side_width = match number_of_other_windows {
+            0 => 0,
+            1 => rect.size.width - main_width,
+            _ => (rect.size.width - main_width) / 2,
+        };
+
+        let mut main_rect = *rect;
+        let mut left_rect = *rect;
+        let mut right_rect = *rect;
+        main_rect.size.width = rect.size.width - side_width;
+        if number_of_other_windows > 1 {
+            left_rect.size.width = side_width;
+            main_rect.origin.x = left_rect.max_x();
+            main_rect.size.width -= side_width;
+        }
+        right_rect.origin.x = main_rect.max_x();
+        right_rect.size.width = side_width;
+
+        arrange_column(main_windows.iter_mut().collect(), &main_rect);
+        let (left_windows, right_windows): (Vec<_>, Vec<_>) = other_windows
+            .iter_mut()
+            .enumerate()
+            .partition(|(i, _)| number_of_other_windows > 1 && i % 2 == 1);
+        arrange_column(
+            left_windows.into_iter().map(|(_, w)| w).collect(),
+            &left_rect,
+        );
+        arrange_column(
+            right_windows.into_iter().map(|(_, w)| w).collect(),
+            &right_rect,
+        );
+
+        clear_window_order(&mut new_windows);
+        (new_windows, Default::default())
+    }
+
+    fn get_state(&self) -> LayoutState {
+        LayoutState::new(vec![self.ratio, self.count as f64], vec![])
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        if let [ratio, count] = state.values.as_slice() {
+            self.ratio = *ratio;
+            self.count = *count as usize;
+        }
+    }
+}
+
+impl Commands for CenteredMasterLayout {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut result = Vec::with_capacity(4);
+
+        result.push(CommandDescriptor::new(
+            "increase_count",
+            "Increase the number of windows in the main area",
+        ));
+        if self.count > 1 {
+            result.push(CommandDescriptor::new(
+                "decrease_count",
+                "Decrease the number of windows in the main area",
+            ));
+        }
+        if self.ratio < 0.9 {
+            result.push(CommandDescriptor::new(
+                "increase_ratio",
+                "Increase the size of the main area",
+            ));
+        }
+        if self.ratio > 0.1 {
+            result.push(CommandDescriptor::new(
+                "decrease_ratio",
+                "Decrease the size of the main area",
+            ));
+        }
+
+        result
+    }
+
+    fn execute_command(&mut self, command: &str, _args: &[Argument]) -> CommandResult {
+        match command {
+            "increase_count" => self.count += 1,
+            "decrease_count" if self.count > 1 => self.count -= 1,
+            "increase_ratio" if self.ratio < 0.9 => self.ratio += 0.05,
+            "decrease_ratio" if self.ratio > 0.1 => self.ratio -= 0.05,
+            "decrease_count" | "increase_ratio" | "decrease_ratio" => {
+                return CommandResult::Unchanged
+            }
+            _ => return CommandResult::unknown_command(command),
+        }
+        CommandResult::Changed
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    artist::Artist,
+    commands::{Argument, CommandDescriptor, CommandResult, Commands},
+    layout::*,
+    window_data::WindowData,
+};
+
+pub fn new_dwindle(ratio: f64) -> Box<DwindleLayout> {
+    Box::new(DwindleLayout {
+        ratio,
+        is_spiral: false,
+    })
+}
+
+pub fn new_spiral(ratio: f64) -> Box<DwindleLayout> {
+    Box::new(DwindleLayout {
+        ratio,
+        is_spiral: true,
+    })
+}
+
+// Each window takes a share of the remaining space, alternating axis. The first split uses
+// the ratio, later ones halve what is left. A dwindle always places windows to the left / top
+// of the remainder, a spiral turns clockwise around it.
+pub struct DwindleLayout {
+    ratio: f64,
+    is_spiral: bool,
+}
+
+impl Layout for DwindleLayout {
+    fn layout(
+        &self,
+        rect: &Bounds,
+        windows: Vec<WindowData>,
+    ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        if windows.is_empty() {
+            return Default::default();
+        }
+
+        let mut new_windows = windows;
+        let number_of_windows = new_windows.len();
+        let mut remaining = *rect;
+        let mut axis = rect.size.largest_axis();
+        for (i, window) in new_windows.iter_mut().enumerate() {
+            if i + 1 == number_of_windows {
+                window.bounds = remaining;
+                break;
+            }
+
+            let ratio = if i == 0 { self.ratio } else { 0.5 };
+            let is_first = !self.is_spiral || i % 4 < 2;
+            let mut taken = remaining;
+            match axis {
+                Axis::X => {
+                    taken.size.width = (remaining.size.width as f64 * ratio).floor() as u16;
+                    remaining.size.width -= taken.size.width;
+                    if is_first {
+                        remaining.origin.x = taken.max_x();
+                    } else {
+                        taken.origin.x = remaining.max_x();
+                    }
+                }
+                Axis::Y => {
+                    taken.size.height = (remaining.size.height as f64 * ratio).floor() as u16;
+                    remaining.size.height -= taken.size.height;
+                    if is_first {
+                        remaining.origin.y = taken.max_y();
+                    } else {
+                        taken.origin.y = remaining.max_y();
+                    }
+                }
+            }
+            window.bounds = taken;
+            axis = axis.orthogonal();
+        }
+        clear_window_order(&mut new_windows);
+        (new_windows, Default::default())
+    }
+
+    fn get_state(&self) -> LayoutState {
+        LayoutState::new(vec![self.ratio], vec![])
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        if let [ratio] = state.values.as_slice() {
+            self.ratio = *ratio;
+        }
+    }
+}
+
+impl Commands for DwindleLayout {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut result = Vec::new();
+        if self.ratio < 0.9 {
+            result.push(CommandDescriptor::new(
+                "increase_ratio",
+                "Increase the size of the first window",
+            ));
+        }
+        if self.ratio > 0.1 {
+            result.push(CommandDescriptor::new(
+                "decrease_ratio",
+                "Decrease the size of the first window",
+            ));
+        }
+        result
+    }
+
+    fn execute_command(&mut self, command: &str, _args: &[Argument]) -> CommandResult {
+        match command {
+            "increase_ratio" if self.ratio < 0.9 => self.ratio += 0.05,
+            "decrease_ratio" if self.ratio > 0.1 => self.ratio -= 0.05,
+            "increase_ratio" | "decrease_ratio" => return CommandResult::Unchanged,
+            _ => return CommandResult::unknown_command(command),
+        }
+        CommandResult::Changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(layout: &DwindleLayout, number_of_windows: u32) -> Vec<Bounds> {
+        let windows = (1..=number_of_windows).map(WindowData::new).collect();
+        let (new_windows, _) = layout.layout(&Bounds::new(0, 0, 800, 600), windows);
+        new_windows.iter().map(|w| w.bounds).collect()
+    }
+
+    #[test]
+    fn single_window_fills_the_rect() {
+        assert_eq!(
+            bounds(&new_dwindle(0.6), 1),
+            vec![Bounds::new(0, 0, 800, 600)]
+        );
+    }
+
+    #[test]
+    fn dwindle_keeps_the_remainder_bottom_right() {
+        assert_eq!(
+            bounds(&new_dwindle(0.5), 4),
+            vec![
+                Bounds::new(0, 0, 400, 600),
+                Bounds::new(400, 0, 400, 300),
+                Bounds::new(400, 300, 200, 300),
+                Bounds::new(600, 300, 200, 300),
+            ]
+        );
+    }
+
+    #[test]
+    fn spiral_turns_clockwise() {
+        assert_eq!(
+            bounds(&new_spiral(0.5), 5),
+            vec![
+                Bounds::new(0, 0, 400, 600),
+                Bounds::new(400, 0, 400, 300),
+                Bounds::new(600, 300, 200, 300),
+                Bounds::new(400, 450, 200, 150),
+                Bounds::new(400, 300, 200, 150),
+            ]
+        );
+    }
+
+    #[test]
+    fn ratio_applies_to_the_first_window_only() {
+        assert_eq!(
+            bounds(&new_dwindle(0.75), 3),
+            vec![
+                Bounds::new(0, 0, 600, 600),
+                Bounds::new(600, 0, 200, 300),
+                Bounds::new(600, 300, 200, 300),
+            ]
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    artist::Artist,
+    commands::{Argument, CommandDescriptor, CommandResult, Commands},
+    layout::*,
+    window_data::WindowData,
+};
+use std::cell::Cell;
+
+pub type BoxedLayoutPredicate = Box<dyn Fn(&Bounds, usize) -> bool>;
+
+// The first child is used while the predicate holds for the rect and number of windows
+pub fn new(
+    predicate: BoxedLayoutPredicate,
+    child_a: Box<dyn Layout>,
+    child_b: Box<dyn Layout>,
+) -> Box<DynamicLayout> {
+    Box::new(DynamicLayout {
+        predicate,
+        is_first_child_active: Cell::new(true),
+        children: (child_a, child_b),
+    })
+}
+
+pub fn switch_on_window_count(
+    count: usize,
+    child_a: Box<dyn Layout>,
+    child_b: Box<dyn Layout>,
+) -> Box<DynamicLayout> {
+    new(
+        Box::new(move |_, number_of_windows| number_of_windows <= count),
+        child_a,
+        child_b,
+    )
+}
+
+pub fn switch_on_available_size(
+    axis: Axis,
+    size_break: u16,
+    child_a: Box<dyn Layout>,
+    child_b: Box<dyn Layout>,
+) -> Box<DynamicLayout> {
+    new(
+        Box::new(move |rect, _| axis.extract_size(rect) < size_break),
+        child_a,
+        child_b,
+    )
+}
+
+pub fn switch_on_prorata_size(
+    axis: Axis,
+    size_break: u16,
+    child_a: Box<dyn Layout>,
+    child_b: Box<dyn Layout>,
+) -> Box<DynamicLayout> {
+    new(
+        Box::new(move |rect, number_of_windows| {
+            (axis.extract_size(rect) / number_of_windows.max(1) as u16) < size_break
+        }),
+        child_a,
+        child_b,
+    )
+}
+
+// Commands go to whichever child was used for the most recent layout
+pub struct DynamicLayout {
+    predicate: BoxedLayoutPredicate,
+    is_first_child_active: Cell<bool>,
+    children: (Box<dyn Layout>, Box<dyn Layout>),
+}
+
+impl DynamicLayout {
+    fn active_child(&self) -> &dyn Layout {
+        if self.is_first_child_active.get() {
+            self.children.0.as_ref()
+        } else {
+            self.children.1.as_ref()
+        }
+    }
+
+    fn active_child_mut(&mut self) -> &mut dyn Layout {
+        if self.is_first_child_active.get() {
+            self.children.0.as_mut()
+        } else {
+            self.children.1.as_mut()
+        }
+    }
+}
+
+impl Layout for DynamicLayout {
+    fn layout(
+        &self,
+        rect: &Bounds,
+        windows: Vec<WindowData>,
+    ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        self.is_first_child_active
+            .set((self.predicate)(rect, windows.len()));
+        self.active_child().layout(rect, windows)
+    }
+
+    fn get_state(&self) -> LayoutState {
+        LayoutState::new(
+            vec![],
+            vec![self.children.0.get_state(), self.children.1.get_state()],
+        )
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        if let [state_0, state_1] = state.children.as_slice() {
+            self.children.0.set_state(state_0);
+            self.children.1.set_state(state_1);
+        }
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.children.0.set_focused_window(window);
+        self.children.1.set_focused_window(window);
+    }
+
+    fn set_settings(&mut self, settings: LayoutSettings) {
+        self.children.0.set_settings(settings);
+        self.children.1.set_settings(settings);
+    }
+
+    fn get_boundaries(&self, rect: &Bounds, windows: &[WindowData]) -> Vec<Boundary> {
+        if (self.predicate)(rect, windows.len()) {
+            self.children.0.get_boundaries(rect, windows)
+        } else {
+            self.children.1.get_boundaries(rect, windows)
+        }
+    }
+
+    fn has_single_tile(&self, rect: &Bounds, windows: &[WindowData]) -> bool {
+        if (self.predicate)(rect, windows.len()) {
+            self.children.0.has_single_tile(rect, windows)
+        } else {
+            self.children.1.has_single_tile(rect, windows)
+        }
+    }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.active_child().is_weighted(window)
+    }
+}
+
+impl Commands for DynamicLayout {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        self.active_child().get_commands()
+    }
+
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
+        self.active_child_mut().execute_command(command, args)
+    }
+}
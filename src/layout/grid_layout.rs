@@ -1,10 +1,92 @@
-use crate::{artist::Artist, commands::Commands, layout::*, window_data::WindowData};
+use crate::{
+    artist::Artist,
+    commands::{Argument, CommandDescriptor, CommandResult, Commands},
+    layout::*,
+    window_data::WindowData,
+};
+use std::cell::Cell;
 
+// A grid as square as possible, filled row by row, e.g.
+// `grid_layout::new().with_rows(2).column_major()`
 pub fn new() -> Box<GridLayout> {
-    Box::new(GridLayout {})
+    Box::new(GridLayout {
+        columns: None,
+        rows: None,
+        is_column_major: false,
+        spans_last_line: false,
+        number_of_windows: Cell::new(0),
+    })
 }
 
-pub struct GridLayout {}
+fn cell_bounds(rect: &Bounds, column: usize, columns: usize, row: usize, rows: usize) -> Bounds {
+    let width = rect.size.width as usize;
+    let height = rect.size.height as usize;
+    let left = width * column / columns;
+    let right = width * (column + 1) / columns;
+    let top = height * row / rows;
+    let bottom = height * (row + 1) / rows;
+    Bounds::new(
+        rect.origin.x + left as i16,
+        rect.origin.y + top as i16,
+        (right - left) as u16,
+        (bottom - top) as u16,
+    )
+}
+
+pub struct GridLayout {
+    columns: Option<usize>,
+    rows: Option<usize>,
+    is_column_major: bool,
+    spans_last_line: bool, // the last row, or column if column major, fills the rect
+    number_of_windows: Cell<usize>, // as of the most recent layout
+}
+
+impl GridLayout {
+    // Fixed columns take precedence over fixed rows
+    pub fn with_columns(mut self: Box<Self>, columns: usize) -> Box<Self> {
+        self.columns = Some(columns).filter(|columns| *columns > 0);
+        self
+    }
+
+    pub fn with_rows(mut self: Box<Self>, rows: usize) -> Box<Self> {
+        self.rows = Some(rows).filter(|rows| *rows > 0);
+        self
+    }
+
+    pub fn column_major(mut self: Box<Self>) -> Box<Self> {
+        self.is_column_major = true;
+        self
+    }
+
+    pub fn spanning_last_line(mut self: Box<Self>) -> Box<Self> {
+        self.spans_last_line = true;
+        self
+    }
+
+    fn dimensions(&self, number_of_windows: usize) -> (usize, usize) {
+        let number_of_windows = number_of_windows.max(1);
+        let columns = match (self.columns, self.rows) {
+            (Some(columns), _) => columns.min(number_of_windows),
+            (None, Some(rows)) => {
+                let rows = rows.min(number_of_windows);
+                number_of_windows.div_ceil(rows)
+            }
+            (None, None) => (number_of_windows as f64).sqrt().ceil() as usize,
+        }
+        .max(1);
+        let rows = number_of_windows.div_ceil(columns);
+        if self.is_column_major {
+            // Filling columns first may leave the last ones empty
+            (number_of_windows.div_ceil(rows), rows)
+        } else {
+            (columns, rows)
+        }
+    }
+
+    fn current_columns(&self) -> usize {
+        self.dimensions(self.number_of_windows.get()).0
+    }
+}
 
 impl Layout for GridLayout {
     fn layout(
@@ -12,39 +94,145 @@ impl Layout for GridLayout {
         rect: &Bounds,
         windows: Vec<WindowData>,
     ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        self.number_of_windows.set(windows.len());
+
         if windows.is_empty() {
             return Default::default();
         }
 
-        let columns = (windows.len() as f64).sqrt().ceil() as u16;
-        let rows = (windows.len() as u16 + columns - 1) / columns;
+        let number_of_windows = windows.len();
+        let (columns, rows) = self.dimensions(number_of_windows);
 
-        let screen_gap = 5;
-        let window_gap = 5;
+        let mut new_windows = windows;
+        for (i, window) in new_windows.iter_mut().enumerate() {
+            window.bounds = if self.is_column_major {
+                let (column, row) = (i / rows, i % rows);
+                let rows_in_column = if self.spans_last_line && column + 1 == columns {
+                    number_of_windows - column * rows
+                } else {
+                    rows
+                };
+                cell_bounds(rect, column, columns, row, rows_in_column)
+            } else {
+                let (column, row) = (i % columns, i / columns);
+                let columns_in_row = if self.spans_last_line && row + 1 == rows {
+                    number_of_windows - row * columns
+                } else {
+                    columns
+                };
+                cell_bounds(rect, column, columns_in_row, row, rows)
+            };
+        }
+        clear_window_order(&mut new_windows);
+        (new_windows, Default::default())
+    }
 
-        let cell_width = (rect.size.width - screen_gap * 2) / columns;
-        let cell_height = (rect.size.height - screen_gap * 2) / rows;
+    // 0 stands for neither columns nor rows being fixed
+    fn get_state(&self) -> LayoutState {
+        LayoutState::new(
+            vec![
+                self.columns.unwrap_or(0) as f64,
+                self.rows.unwrap_or(0) as f64,
+            ],
+            vec![],
+        )
+    }
 
-        let mut row = 0;
-        let mut column = 0;
+    fn set_state(&mut self, state: &LayoutState) {
+        if let [columns, rows] = state.values.as_slice() {
+            self.columns = Some(*columns as usize).filter(|columns| *columns > 0);
+            self.rows = Some(*rows as usize).filter(|rows| *rows > 0);
+        }
+    }
+}
 
-        let width = cell_width - 2 * window_gap;
-        let height = cell_height - 2 * window_gap;
+impl Commands for GridLayout {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands = Vec::new();
+        let number_of_windows = self.number_of_windows.get();
+        let columns = self.current_columns();
+        if columns < number_of_windows {
+            commands.push(CommandDescriptor::new(
+                "increase_column_count",
+                "Increase the number of columns",
+            ));
+        }
+        if columns > 1 {
+            commands.push(CommandDescriptor::new(
+                "decrease_column_count",
+                "Decrease the number of columns",
+            ));
+        }
+        if self.columns.is_some() {
+            commands.push(CommandDescriptor::new(
+                "reset_column_count",
+                "Choose the number of columns automatically",
+            ));
+        }
+        commands
+    }
 
-        let mut new_windows = windows.to_vec();
-        for window in new_windows.iter_mut() {
-            let x = rect.origin.x + (screen_gap + cell_width * column + window_gap) as i16;
-            let y = rect.origin.y + (screen_gap + cell_height * row + window_gap) as i16;
-            column += 1;
-            if column == columns {
-                column = 0;
-                row += 1;
+    fn execute_command(&mut self, command: &str, _args: &[Argument]) -> CommandResult {
+        let number_of_windows = self.number_of_windows.get();
+        let columns = self.current_columns();
+        match command {
+            "increase_column_count" if columns < number_of_windows => {
+                self.columns = Some(columns + 1)
             }
-            window.bounds = Bounds::new(x, y, width, height);
+            "decrease_column_count" if columns > 1 => self.columns = Some(columns - 1),
+            "reset_column_count" if self.columns.is_some() => self.columns = None,
+            "increase_column_count" | "decrease_column_count" | "reset_column_count" => {
+                return CommandResult::Unchanged
+            }
+            _ => return CommandResult::unknown_command(command),
         }
-        clear_window_order(&mut new_windows);
-        (new_windows, Default::default())
+        CommandResult::Changed
     }
 }
 
-impl Commands for GridLayout {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_grid_grows_columns_first() {
+        let grid = new();
+        assert_eq!(grid.dimensions(0), (1, 1));
+        assert_eq!(grid.dimensions(2), (2, 1));
+        assert_eq!(grid.dimensions(3), (2, 2));
+        assert_eq!(grid.dimensions(5), (3, 2));
+        assert_eq!(grid.dimensions(9), (3, 3));
+    }
+
+    #[test]
+    fn fixed_columns_take_precedence_over_rows() {
+        let grid = new().with_columns(2).with_rows(4);
+        assert_eq!(grid.dimensions(5), (2, 3));
+        assert_eq!(grid.dimensions(1), (1, 1));
+    }
+
+    #[test]
+    fn fixed_rows_add_columns() {
+        let grid = new().with_rows(2);
+        assert_eq!(grid.dimensions(5), (3, 2));
+        assert_eq!(grid.dimensions(1), (1, 1));
+    }
+
+    #[test]
+    fn column_major_grid_drops_empty_columns() {
+        // Filling 2 rows per column, 4 windows leave the third column empty
+        let grid = new().with_columns(3).column_major();
+        assert_eq!(grid.dimensions(4), (2, 2));
+        assert_eq!(grid.dimensions(7), (3, 3));
+    }
+
+    #[test]
+    fn state_restores_fixed_columns_and_rows() {
+        let mut grid = new().with_rows(2);
+        let state = grid.get_state();
+        grid.columns = Some(3);
+        grid.rows = None;
+        grid.set_state(&state);
+        assert_eq!((grid.columns, grid.rows), (None, Some(2)));
+    }
+}
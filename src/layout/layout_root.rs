@@ -1,4 +1,9 @@
-use crate::{artist::Artist, commands::Commands, layout::*, window_data::WindowData};
+use crate::{
+    artist::Artist,
+    commands::{Argument, CommandDescriptor, CommandResult, Commands},
+    layout::*,
+    window_data::WindowData,
+};
 
 pub fn new(name: &str, child: Box<dyn Layout>) -> LayoutRoot {
     LayoutRoot::new(name, child)
@@ -30,14 +35,42 @@ impl Layout for LayoutRoot {
     ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
         self.child.layout(rect, windows)
     }
+
+    fn get_state(&self) -> LayoutState {
+        self.child.get_state()
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        self.child.set_state(state);
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.child.set_focused_window(window);
+    }
+
+    fn set_settings(&mut self, settings: LayoutSettings) {
+        self.child.set_settings(settings);
+    }
+
+    fn get_boundaries(&self, rect: &Bounds, windows: &[WindowData]) -> Vec<Boundary> {
+        self.child.get_boundaries(rect, windows)
+    }
+
+    fn has_single_tile(&self, rect: &Bounds, windows: &[WindowData]) -> bool {
+        self.child.has_single_tile(rect, windows)
+    }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.child.is_weighted(window)
+    }
 }
 
 impl Commands for LayoutRoot {
-    fn get_commands(&self) -> Vec<String> {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
         self.child.get_commands()
     }
 
-    fn execute_command(&mut self, command: &str, args: &[&str]) -> bool {
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
         self.child.execute_command(command, args)
     }
 }
@@ -1,12 +1,19 @@
 use crate::{artist::Artist, commands::Commands, layout::*, window_data::WindowData};
+use std::cell::RefCell;
 
 pub fn new(direction: Direction, axis: Axis) -> Box<LinearLayout> {
-    Box::new(LinearLayout { direction, axis })
+    Box::new(LinearLayout {
+        direction,
+        axis,
+        windows: Default::default(),
+    })
 }
 
+// Windows share the rect in proportion to their weights
 pub struct LinearLayout {
     axis: Axis,
     direction: Direction,
+    windows: RefCell<Vec<xcb::Window>>, // as of the most recent layout
 }
 
 impl Layout for LinearLayout {
@@ -15,31 +22,75 @@ impl Layout for LinearLayout {
         rect: &Bounds,
         windows: Vec<WindowData>,
     ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        *self.windows.borrow_mut() = windows.iter().map(|w| w.window()).collect();
         if windows.is_empty() {
             return Default::default();
         }
 
+        let total_size = self.axis.extract_size(rect);
+        let total_weight: f64 = windows.iter().map(|w| w.weight).sum();
+        let number_of_windows = windows.len();
+
         let mut new_windows = windows.to_vec();
         let mut r = *rect;
-        match self.axis {
-            Axis::X => {
-                r.size.width = r.size.width / windows.len() as u16;
-                for window in new_windows.iter_mut() {
-                    window.bounds = r;
-                    r.origin.x += r.size.width as i16;
+        let mut offset = 0;
+        for (i, window) in new_windows.iter_mut().enumerate() {
+            // The last window absorbs any rounding
+            let size = if i + 1 == number_of_windows {
+                total_size - offset
+            } else {
+                (total_size as f64 * window.weight / total_weight).floor() as u16
+            };
+            match self.axis {
+                Axis::X => {
+                    r.origin.x = rect.origin.x + offset as i16;
+                    r.size.width = size;
                 }
-            }
-            Axis::Y => {
-                r.size.height = r.size.height / windows.len() as u16;
-                for window in new_windows.iter_mut() {
-                    window.bounds = r;
-                    r.origin.y += r.size.height as i16;
+                Axis::Y => {
+                    r.origin.y = rect.origin.y + offset as i16;
+                    r.size.height = size;
                 }
             }
-        };
+            window.bounds = r;
+            offset += size;
+        }
         clear_window_order(&mut new_windows);
         (new_windows, Default::default())
     }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.windows.borrow().contains(&window)
+    }
 }
 
 impl Commands for LinearLayout {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_share_the_rect_by_weight() {
+        let layout = new(Direction::Increasing, Axis::Y);
+        let mut windows = (1..=3).map(WindowData::new).collect::<Vec<_>>();
+        windows[0].weight = 2.0;
+        let (new_windows, _) = layout.layout(&Bounds::new(0, 0, 100, 401), windows);
+        assert_eq!(
+            new_windows.iter().map(|w| w.bounds).collect::<Vec<_>>(),
+            vec![
+                Bounds::new(0, 0, 100, 200),
+                Bounds::new(0, 200, 100, 100),
+                Bounds::new(0, 300, 100, 101),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_windows_laid_out_are_weighted() {
+        let layout = new(Direction::Increasing, Axis::X);
+        assert!(!layout.is_weighted(1));
+        layout.layout(&Bounds::new(0, 0, 100, 100), vec![WindowData::new(1)]);
+        assert!(layout.is_weighted(1));
+        assert!(!layout.is_weighted(2));
+    }
+}
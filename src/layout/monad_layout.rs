@@ -21,3 +21,14 @@ pub fn new_stack(direction: Direction, axis: Axis, ratio: f64, count: usize) ->
         stack_layout::new(),
     )
 }
+
+pub fn new_tabbed(direction: Direction, axis: Axis, ratio: f64, count: usize) -> Box<dyn Layout> {
+    split_layout::new(
+        direction,
+        axis,
+        ratio,
+        count,
+        linear_layout::new(direction, axis),
+        tabbed_layout::new(),
+    )
+}
@@ -0,0 +1,153 @@
+use crate::{
+    artist::Artist, commands::Commands, connection::*, layout::*, window_data::WindowData,
+};
+
+pub fn new() -> Box<MonocleLayout> {
+    Box::new(MonocleLayout {
+        focused_window: None,
+    })
+}
+
+// Shows the position of the current window in the stack, e.g. "2/5 — Firefox", in the top
+// right corner of the rect
+struct StackCounterArtist {
+    rect: Bounds,
+    windows: Vec<xcb::Window>,
+    current_window: xcb::Window,
+}
+
+impl StackCounterArtist {
+    const FONT_FACE: &'static str = "Noto Sans Mono";
+    const FONT_SIZE: u16 = 12;
+
+    const MARGIN: Size = Size::new(8, 4);
+    const CORNER_OFFSET: i16 = 8;
+    const MAXIMUM_TITLE_LENGTH: usize = 40;
+
+    fn configure_font(&self, context: &cairo::Context) {
+        context.select_font_face(
+            Self::FONT_FACE,
+            cairo::FontSlant::Normal,
+            cairo::FontWeight::Bold,
+        );
+        context.set_font_size(Self::FONT_SIZE as f64);
+    }
+
+    fn text(&self) -> String {
+        let index = self
+            .windows
+            .iter()
+            .position(|w| *w == self.current_window)
+            .unwrap_or(0);
+        let title = get_string_property(self.current_window, *ATOM__NET_WM_NAME);
+        let mut text = format!("{}/{}", index + 1, self.windows.len());
+        if !title.is_empty() {
+            text.push_str(" — ");
+            text.extend(title.chars().take(Self::MAXIMUM_TITLE_LENGTH));
+        }
+        text
+    }
+}
+
+impl Artist for StackCounterArtist {
+    fn calculate_bounds(&self, window: xcb::Window) -> Option<Bounds> {
+        if let Ok(surface) = get_cairo_surface(window) {
+            let context = cairo::Context::new(&surface);
+
+            self.configure_font(&context);
+            let font_extents = context.font_extents();
+            let text_extents = context.text_extents(&self.text());
+
+            let width =
+                (Self::MARGIN.width + text_extents.x_advance.ceil() as u16 + Self::MARGIN.width)
+                    .min(self.rect.size.width);
+            let height =
+                Self::MARGIN.height + font_extents.height.ceil() as u16 + Self::MARGIN.height;
+
+            return Some(Bounds::new(
+                self.rect.origin.x + self.rect.size.width as i16
+                    - width as i16
+                    - Self::CORNER_OFFSET,
+                self.rect.origin.y + Self::CORNER_OFFSET,
+                width,
+                height,
+            ));
+        }
+
+        None
+    }
+
+    fn draw(&self, window: xcb::Window) {
+        if let Ok(geometry) = xcb::get_geometry(connection(), window).get_reply() {
+            if let Ok(surface) = get_cairo_surface(window) {
+                let context = cairo::Context::new(&surface);
+
+                context.set_source_rgb(0.125, 0.375, 0.5);
+                context.rectangle(0.0, 0.0, geometry.width() as f64, geometry.height() as f64);
+                context.fill();
+
+                self.configure_font(&context);
+                let font_extents = context.font_extents();
+                context.set_source_rgb(1.0, 1.0, 1.0);
+                context.move_to(
+                    Self::MARGIN.width as f64,
+                    Self::MARGIN.height as f64 + font_extents.ascent,
+                );
+                context.show_text(&self.text());
+            }
+        }
+    }
+}
+
+// Every window fills the whole rect, with the focused one on top
+pub struct MonocleLayout {
+    focused_window: Option<xcb::Window>,
+}
+
+impl Layout for MonocleLayout {
+    fn layout(
+        &self,
+        rect: &Bounds,
+        windows: Vec<WindowData>,
+    ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        if windows.is_empty() {
+            return Default::default();
+        }
+
+        let mut new_windows = windows;
+        for window in new_windows.iter_mut() {
+            window.bounds = *rect;
+        }
+        compute_window_order(&mut new_windows);
+
+        // The focused window is on top if it belongs to the stack
+        let current_window = self
+            .focused_window
+            .filter(|focused_window| new_windows.iter().any(|w| w.window() == *focused_window))
+            .unwrap_or_else(|| {
+                new_windows
+                    .iter()
+                    .max_by_key(|w| w.order)
+                    .map(|w| w.window())
+                    .unwrap()
+            });
+        let artists: Vec<Box<dyn Artist>> = vec![Box::new(StackCounterArtist {
+            rect: *rect,
+            windows: new_windows.iter().map(|w| w.window()).collect(),
+            current_window,
+        })];
+
+        (new_windows, artists)
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.focused_window = window;
+    }
+
+    // Every window has the same bounds
+    fn has_single_tile(&self, _rect: &Bounds, windows: &[WindowData]) -> bool {
+        windows.iter().any(|w| !w.is_floating)
+    }
+}
+
+impl Commands for MonocleLayout {}
@@ -0,0 +1,228 @@
+use crate::{
+    artist::Artist,
+    commands::{Argument, ArgumentType, CommandDescriptor, CommandResult, Commands, Parameter},
+    connection::*,
+    layout::*,
+    window_data::WindowData,
+};
+
+// Called with the index of the window among those being laid out, so a configuration can
+// route windows by their position too
+pub type BoxedWindowPredicate = Box<dyn Fn(usize, &WindowData) -> bool>;
+
+// Matches either the instance or the class name, like the {class:} placeholder
+pub fn with_class(class_name: &str) -> BoxedWindowPredicate {
+    let class_name = class_name.to_owned();
+    Box::new(move |_, window| {
+        get_ascii_strings_property(window.window(), xcb::ATOM_WM_CLASS).contains(&class_name)
+    })
+}
+
+pub fn with_title_containing(text: &str) -> BoxedWindowPredicate {
+    let text = text.to_owned();
+    Box::new(move |_, window| {
+        get_string_property(window.window(), *ATOM__NET_WM_NAME).contains(&text)
+    })
+}
+
+pub fn with_tag(tag: &str) -> BoxedWindowPredicate {
+    let tag = tag.to_owned();
+    Box::new(move |_, window| window.tags.contains(&tag))
+}
+
+pub fn any(predicates: Vec<BoxedWindowPredicate>) -> BoxedWindowPredicate {
+    Box::new(move |index, window| predicates.iter().any(|p| p(index, window)))
+}
+
+// Windows passing the predicate are laid out by the first child, the others by the second.
+// When either group is empty the other one has the whole rect.
+pub fn new(
+    direction: Direction,
+    axis: Axis,
+    ratio: f64,
+    predicate: BoxedWindowPredicate,
+    child_a: Box<dyn Layout>,
+    child_b: Box<dyn Layout>,
+) -> Box<PartitionLayout> {
+    Box::new(PartitionLayout {
+        direction,
+        axis,
+        ratio,
+        predicate,
+        children: (child_a, child_b),
+    })
+}
+
+pub struct PartitionLayout {
+    axis: Axis,
+    direction: Direction,
+    ratio: f64,
+    predicate: BoxedWindowPredicate,
+    children: (Box<dyn Layout>, Box<dyn Layout>),
+}
+
+impl PartitionLayout {
+    // Returns the windows passing the predicate and those failing it
+    fn partition(&self, windows: Vec<WindowData>) -> (Vec<WindowData>, Vec<WindowData>) {
+        let (passing_windows, failing_windows): (Vec<_>, Vec<_>) = windows
+            .into_iter()
+            .enumerate()
+            .partition(|(i, w)| (self.predicate)(*i, w));
+        (
+            passing_windows.into_iter().map(|(_, w)| w).collect(),
+            failing_windows.into_iter().map(|(_, w)| w).collect(),
+        )
+    }
+}
+
+impl Layout for PartitionLayout {
+    fn layout(
+        &self,
+        rect: &Bounds,
+        windows: Vec<WindowData>,
+    ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        if windows.is_empty() {
+            return Default::default();
+        }
+
+        let window_ids = windows.iter().map(|w| w.window()).collect::<Vec<_>>();
+        let (passing_windows, failing_windows) = self.partition(windows);
+
+        let (mut new_windows, artists) = if passing_windows.is_empty() {
+            self.children.1.layout(rect, failing_windows)
+        } else if failing_windows.is_empty() {
+            self.children.0.layout(rect, passing_windows)
+        } else {
+            let (rect_1, rect_2) = split_rect(rect, self.axis, self.direction, self.ratio);
+
+            let (mut new_windows, mut artists) = self.children.0.layout(&rect_1, passing_windows);
+            let (mut new_windows_2, mut artists_2) =
+                self.children.1.layout(&rect_2, failing_windows);
+            new_windows.extend(new_windows_2.drain(0..));
+            artists.extend(artists_2.drain(0..));
+            (new_windows, artists)
+        };
+
+        // The workspace keeps the windows in the order they are returned, so routing must not
+        // reorder them
+        new_windows.sort_by_key(|w| window_ids.iter().position(|id| *id == w.window()));
+        (new_windows, artists)
+    }
+
+    fn get_state(&self) -> LayoutState {
+        LayoutState::new(
+            vec![self.ratio],
+            vec![self.children.0.get_state(), self.children.1.get_state()],
+        )
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        if let ([ratio], [state_0, state_1]) = (state.values.as_slice(), state.children.as_slice())
+        {
+            self.ratio = *ratio;
+            self.children.0.set_state(state_0);
+            self.children.1.set_state(state_1);
+        }
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.children.0.set_focused_window(window);
+        self.children.1.set_focused_window(window);
+    }
+
+    fn set_settings(&mut self, settings: LayoutSettings) {
+        self.children.0.set_settings(settings);
+        self.children.1.set_settings(settings);
+    }
+
+    fn get_boundaries(&self, rect: &Bounds, windows: &[WindowData]) -> Vec<Boundary> {
+        let (passing_windows, failing_windows) = self.partition(windows.to_vec());
+
+        if passing_windows.is_empty() {
+            combine_child_boundaries(vec![
+                vec![],
+                self.children.1.get_boundaries(rect, &failing_windows),
+            ])
+        } else if failing_windows.is_empty() {
+            combine_child_boundaries(vec![self.children.0.get_boundaries(rect, &passing_windows)])
+        } else {
+            let (rect_1, rect_2) = split_rect(rect, self.axis, self.direction, self.ratio);
+            let mut boundaries = vec![Boundary::new(rect, self.axis, self.direction, self.ratio)];
+            boundaries.extend(combine_child_boundaries(vec![
+                self.children.0.get_boundaries(&rect_1, &passing_windows),
+                self.children.1.get_boundaries(&rect_2, &failing_windows),
+            ]));
+            boundaries
+        }
+    }
+
+    fn has_single_tile(&self, rect: &Bounds, windows: &[WindowData]) -> bool {
+        let (passing_windows, failing_windows) = self.partition(windows.to_vec());
+        if passing_windows.is_empty() {
+            self.children.1.has_single_tile(rect, &failing_windows)
+        } else if failing_windows.is_empty() {
+            self.children.0.has_single_tile(rect, &passing_windows)
+        } else {
+            false
+        }
+    }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.children.0.is_weighted(window) || self.children.1.is_weighted(window)
+    }
+}
+
+impl Commands for PartitionLayout {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let c0 = self.children.0.get_commands();
+        let c1 = self.children.1.get_commands();
+
+        let mut result = Vec::with_capacity(c0.len() + c1.len() + 3);
+
+        if self.ratio < 0.9 {
+            result.push(CommandDescriptor::new(
+                "increase_ratio",
+                "Increase the size of the area for matching windows",
+            ));
+        }
+        if self.ratio > 0.1 {
+            result.push(CommandDescriptor::new(
+                "decrease_ratio",
+                "Decrease the size of the area for matching windows",
+            ));
+        }
+        result.push(
+            CommandDescriptor::new(
+                "set_ratio:",
+                "Set the size of the area for matching windows",
+            )
+            .with_parameter(Parameter::new("thousandths", ArgumentType::Number)),
+        );
+
+        result.extend(combine_child_commands(&[c0, c1]));
+
+        result
+    }
+
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
+        if command.starts_with("0/") {
+            self.children.0.execute_command(command.split_at(2).1, args)
+        } else if command.starts_with("1/") {
+            self.children.1.execute_command(command.split_at(2).1, args)
+        } else {
+            match command {
+                "increase_ratio" if self.ratio < 0.9 => self.ratio += 0.05,
+                "decrease_ratio" if self.ratio > 0.1 => self.ratio -= 0.05,
+                "set_ratio:" => match args {
+                    [Argument::Number(thousandths)] => {
+                        self.ratio = (*thousandths as f64 / 1000.0).clamp(0.1, 0.9)
+                    }
+                    _ => return CommandResult::invalid_arguments(command),
+                },
+                "increase_ratio" | "decrease_ratio" => return CommandResult::Unchanged,
+                _ => return CommandResult::unknown_command(command),
+            }
+            CommandResult::Changed
+        }
+    }
+}
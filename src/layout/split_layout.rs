@@ -1,4 +1,9 @@
-use crate::{artist::Artist, commands::Commands, layout::*, window_data::WindowData};
+use crate::{
+    artist::Artist,
+    commands::{Argument, ArgumentType, CommandDescriptor, CommandResult, Commands, Parameter},
+    layout::*,
+    window_data::WindowData,
+};
 
 pub fn new(
     direction: Direction,
@@ -35,27 +40,7 @@ impl Layout for SplitLayout {
             return Default::default();
         }
 
-        let mut rect_1 = *rect;
-        let mut rect_2 = *rect;
-
-        match self.axis {
-            Axis::X => {
-                rect_1.size.width = (rect.size.width as f64 * self.ratio).floor() as u16;
-                rect_2.size.width = rect.size.width - rect_1.size.width;
-                match self.direction {
-                    Direction::Increasing => rect_2.origin.x = rect_1.max_x(),
-                    Direction::Decreasing => rect_1.origin.x = rect_2.max_x(),
-                }
-            }
-            Axis::Y => {
-                rect_1.size.height = (rect.size.height as f64 * self.ratio).floor() as u16;
-                rect_2.size.height = rect.size.height - rect_1.size.height;
-                match self.direction {
-                    Direction::Increasing => rect_2.origin.y = rect_1.max_y(),
-                    Direction::Decreasing => rect_1.origin.y = rect_2.max_y(),
-                }
-            }
-        }
+        let (rect_1, rect_2) = split_rect(rect, self.axis, self.direction, self.ratio);
 
         if windows.len() > self.count {
             let (w1, w2) = windows.split_at(self.count);
@@ -68,33 +53,100 @@ impl Layout for SplitLayout {
             self.children.0.layout(&rect_1, windows)
         }
     }
+
+    fn get_state(&self) -> LayoutState {
+        LayoutState::new(
+            vec![self.ratio, self.count as f64],
+            vec![self.children.0.get_state(), self.children.1.get_state()],
+        )
+    }
+
+    fn set_state(&mut self, state: &LayoutState) {
+        if let ([ratio, count], [state_0, state_1]) =
+            (state.values.as_slice(), state.children.as_slice())
+        {
+            self.ratio = *ratio;
+            self.count = *count as usize;
+            self.children.0.set_state(state_0);
+            self.children.1.set_state(state_1);
+        }
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.children.0.set_focused_window(window);
+        self.children.1.set_focused_window(window);
+    }
+
+    fn set_settings(&mut self, settings: LayoutSettings) {
+        self.children.0.set_settings(settings);
+        self.children.1.set_settings(settings);
+    }
+
+    fn get_boundaries(&self, rect: &Bounds, windows: &[WindowData]) -> Vec<Boundary> {
+        let (rect_1, rect_2) = split_rect(rect, self.axis, self.direction, self.ratio);
+        if windows.len() > self.count {
+            let (w1, w2) = windows.split_at(self.count);
+            let mut boundaries = vec![Boundary::new(rect, self.axis, self.direction, self.ratio)];
+            boundaries.extend(combine_child_boundaries(vec![
+                self.children.0.get_boundaries(&rect_1, w1),
+                self.children.1.get_boundaries(&rect_2, w2),
+            ]));
+            boundaries
+        } else {
+            combine_child_boundaries(vec![self.children.0.get_boundaries(&rect_1, windows)])
+        }
+    }
+
+    fn has_single_tile(&self, rect: &Bounds, windows: &[WindowData]) -> bool {
+        let (rect_1, _) = split_rect(rect, self.axis, self.direction, self.ratio);
+        windows.len() <= self.count && self.children.0.has_single_tile(&rect_1, windows)
+    }
+
+    fn is_weighted(&self, window: xcb::Window) -> bool {
+        self.children.0.is_weighted(window) || self.children.1.is_weighted(window)
+    }
 }
 
 impl Commands for SplitLayout {
-    fn get_commands(&self) -> Vec<String> {
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
         let c0 = self.children.0.get_commands();
         let c1 = self.children.1.get_commands();
 
-        let mut result = Vec::with_capacity(c0.len() + c1.len() + 4);
+        let mut result = Vec::with_capacity(c0.len() + c1.len() + 5);
 
-        result.push(String::from("increase_count"));
+        result.push(CommandDescriptor::new(
+            "increase_count",
+            "Increase the number of windows in the main area",
+        ));
         if self.count > 1 {
-            result.push(String::from("decrease_count"));
+            result.push(CommandDescriptor::new(
+                "decrease_count",
+                "Decrease the number of windows in the main area",
+            ));
         }
         if self.ratio < 0.9 {
-            result.push(String::from("increase_ratio"));
+            result.push(CommandDescriptor::new(
+                "increase_ratio",
+                "Increase the size of the main area",
+            ));
         }
         if self.ratio > 0.1 {
-            result.push(String::from("decrease_ratio"));
+            result.push(CommandDescriptor::new(
+                "decrease_ratio",
+                "Decrease the size of the main area",
+            ));
         }
+        result.push(
+            CommandDescriptor::new("set_ratio:", "Set the size of the main area")
+                .with_parameter(Parameter::new("thousandths", ArgumentType::Number)),
+        );
 
-        result.extend(c0.iter().map(|c| format!("0/{}", c)));
-        result.extend(c1.iter().map(|c| format!("1/{}", c)));
+        result.extend(combine_child_commands(&[c0, c1]));
 
         result
     }
 
-    fn execute_command(&mut self, command: &str, args: &[&str]) -> bool {
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
         if command.starts_with("0/") {
             self.children.0.execute_command(command.split_at(2).1, args)
         } else if command.starts_with("1/") {
@@ -105,9 +157,18 @@ impl Commands for SplitLayout {
                 "decrease_count" if self.count > 1 => self.count -= 1,
                 "increase_ratio" if self.ratio < 0.9 => self.ratio += 0.05,
                 "decrease_ratio" if self.ratio > 0.1 => self.ratio -= 0.05,
-                _ => (),
+                "set_ratio:" => match args {
+                    [Argument::Number(thousandths)] => {
+                        self.ratio = (*thousandths as f64 / 1000.0).clamp(0.1, 0.9)
+                    }
+                    _ => return CommandResult::invalid_arguments(command),
+                },
+                "decrease_count" | "increase_ratio" | "decrease_ratio" => {
+                    return CommandResult::Unchanged
+                }
+                _ => return CommandResult::unknown_command(command),
             }
-            true
+            CommandResult::Changed
         }
     }
 }
@@ -79,6 +79,11 @@ impl Layout for StackLayout {
         compute_window_order(&mut new_windows);
         (new_windows, artists)
     }
+
+    // Every window has the same bounds
+    fn has_single_tile(&self, _rect: &Bounds, windows: &[WindowData]) -> bool {
+        windows.iter().any(|w| !w.is_floating)
+    }
 }
 
 impl Commands for StackLayout {}
@@ -0,0 +1,152 @@
+use crate::{
+    artist::Artist, commands::Commands, connection::*, layout::*, window_data::WindowData,
+};
+
+pub fn new() -> Box<TabbedLayout> {
+    Box::new(TabbedLayout {
+        focused_window: None,
+    })
+}
+
+struct TabBarArtist {
+    bounds: Bounds,
+    windows: Vec<xcb::Window>,
+    visible_window: xcb::Window,
+    focused_window: Option<xcb::Window>,
+}
+
+impl TabBarArtist {
+    const FONT_FACE: &'static str = "Noto Sans Mono";
+    const FONT_SIZE: u16 = 12;
+
+    const TEXT_PADDING: u16 = 6;
+
+    fn tab_width(&self) -> u16 {
+        (self.bounds.size.width / self.windows.len() as u16).max(1)
+    }
+}
+
+impl Artist for TabBarArtist {
+    fn calculate_bounds(&self, _window: xcb::Window) -> Option<Bounds> {
+        Some(self.bounds)
+    }
+
+    fn draw(&self, window: xcb::Window) {
+        if let Ok(surface) = get_cairo_surface(window) {
+            let context = cairo::Context::new(&surface);
+
+            context.select_font_face(
+                Self::FONT_FACE,
+                cairo::FontSlant::Normal,
+                cairo::FontWeight::Normal,
+            );
+            context.set_font_size(Self::FONT_SIZE as f64);
+            let font_extents = context.font_extents();
+            let baseline =
+                (self.bounds.size.height as f64 - font_extents.height) / 2.0 + font_extents.ascent;
+
+            let tab_width = self.tab_width();
+            for (i, tab_window) in self.windows.iter().enumerate() {
+                let left = i as u16 * tab_width;
+                // The last tab absorbs any rounding
+                let width = if i + 1 == self.windows.len() {
+                    self.bounds.size.width - left
+                } else {
+                    tab_width
+                };
+
+                if Some(*tab_window) == self.focused_window {
+                    context.set_source_rgb(0.125, 0.375, 0.5);
+                } else if *tab_window == self.visible_window {
+                    context.set_source_rgb(0.35, 0.35, 0.35);
+                } else {
+                    context.set_source_rgb(0.2, 0.2, 0.2);
+                }
+                context.rectangle(
+                    left as f64,
+                    0.0,
+                    width as f64,
+                    self.bounds.size.height as f64,
+                );
+                context.fill();
+
+                context.save();
+                context.rectangle(
+                    (left + Self::TEXT_PADDING) as f64,
+                    0.0,
+                    width.saturating_sub(2 * Self::TEXT_PADDING) as f64,
+                    self.bounds.size.height as f64,
+                );
+                context.clip();
+                context.set_source_rgb(1.0, 1.0, 1.0);
+                context.move_to((left + Self::TEXT_PADDING) as f64, baseline);
+                context.show_text(&get_string_property(*tab_window, *ATOM__NET_WM_NAME));
+                context.restore();
+            }
+        }
+    }
+
+    fn window_at(&self, position: Position) -> Option<xcb::Window> {
+        let index = (position.x.max(0) as u16 / self.tab_width()) as usize;
+        self.windows.get(index.min(self.windows.len() - 1)).copied()
+    }
+}
+
+pub struct TabbedLayout {
+    focused_window: Option<xcb::Window>,
+}
+
+impl TabbedLayout {
+    const TAB_BAR_HEIGHT: u16 = 20;
+}
+
+impl Layout for TabbedLayout {
+    fn layout(
+        &self,
+        rect: &Bounds,
+        windows: Vec<WindowData>,
+    ) -> (Vec<WindowData>, Vec<Box<dyn Artist>>) {
+        if windows.is_empty() {
+            return Default::default();
+        }
+
+        let bar_height = Self::TAB_BAR_HEIGHT.min(rect.size.height);
+        let r = Bounds::new(
+            rect.origin.x,
+            rect.origin.y + bar_height as i16,
+            rect.size.width,
+            rect.size.height - bar_height,
+        );
+
+        let mut new_windows = windows;
+        for window in new_windows.iter_mut() {
+            window.bounds = r;
+        }
+        compute_window_order(&mut new_windows);
+
+        let visible_window = new_windows
+            .iter()
+            .max_by_key(|w| w.order)
+            .map(|w| w.window())
+            .unwrap();
+        let artists: Vec<Box<dyn Artist>> = vec![Box::new(TabBarArtist {
+            bounds: Bounds::new(rect.origin.x, rect.origin.y, rect.size.width, bar_height),
+            windows: new_windows.iter().map(|w| w.window()).collect(),
+            visible_window,
+            focused_window: self.focused_window,
+        })];
+
+        (new_windows, artists)
+    }
+
+    fn set_focused_window(&mut self, window: Option<xcb::Window>) {
+        self.focused_window = window;
+    }
+
+    // Every window has the same bounds
+    fn has_single_tile(&self, _rect: &Bounds, windows: &[WindowData]) -> bool {
+        windows.iter().any(|w| !w.is_floating)
+    }
+}
+
+impl Commands for TabbedLayout {}
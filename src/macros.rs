@@ -0,0 +1,129 @@
+use super::{
+    command_parser::{self, Invocation, Word, WordPart},
+    commands::{Argument, CommandDescriptor, Parameter},
+};
+
+// A named sequence of commands. Unquoted words of the form $name in the body are replaced by
+// the argument bound to the parameter of that name, which is never expanded further.
+#[derive(Debug, Clone)]
+pub struct Macro {
+    descriptor: CommandDescriptor,
+    body: Vec<Invocation>,
+}
+
+impl Macro {
+    pub fn new(
+        name: &str,
+        help: &str,
+        parameters: Vec<Parameter>,
+        body: &str,
+    ) -> Result<Macro, String> {
+        let body = command_parser::parse(body)
+            .map_err(|message| format!("Invalid body for macro {}: {}", name, message))?;
+        let descriptor = parameters.into_iter().fold(
+            CommandDescriptor::new(name, help),
+            CommandDescriptor::with_parameter,
+        );
+        Ok(Macro { descriptor, body })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.descriptor.name
+    }
+
+    pub fn descriptor(&self) -> &CommandDescriptor {
+        &self.descriptor
+    }
+
+    pub fn expand(&self, args: &[Argument]) -> Vec<Invocation> {
+        self.body
+            .iter()
+            .map(|invocation| Invocation {
+                command: invocation.command.clone(),
+                words: invocation
+                    .words
+                    .iter()
+                    .map(|word| self.substitute(word, args))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn substitute(&self, word: &Word, args: &[Argument]) -> Word {
+        if let (false, [WordPart::Text(text)]) = (word.is_quoted, word.parts.as_slice()) {
+            if !text.starts_with('$') {
+                return word.clone();
+            }
+            let name = &text[1..];
+            if let Some(arg) = self
+                .descriptor
+                .parameters
+                .iter()
+                .position(|p| p.name == name)
+                .and_then(|index| args.get(index))
+            {
+                return Word::text(&arg.to_string());
+            }
+        }
+        word.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::ArgumentType;
+
+    fn follow() -> Macro {
+        Macro::new(
+            "move_focused_window_and_follow:",
+            "",
+            vec![Parameter::new("workspace", ArgumentType::String)],
+            "move_focused_window_to_workspace_named: $workspace; \
+             switch_to_workspace_named: $workspace '$workspace' $other",
+        )
+        .unwrap()
+    }
+
+    fn texts(invocations: &[Invocation]) -> Vec<Vec<String>> {
+        invocations
+            .iter()
+            .map(|i| {
+                std::iter::once(i.command.clone())
+                    .chain(i.words.iter().map(|w| w.to_text()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parameters_are_substituted() {
+        assert_eq!(
+            texts(&follow().expand(&[Argument::String("web".into())])),
+            vec![
+                vec!["move_focused_window_to_workspace_named:", "web"],
+                vec!["switch_to_workspace_named:", "web", "$workspace", "$other"],
+            ]
+        );
+    }
+
+    #[test]
+    fn substituted_arguments_are_not_expanded_further() {
+        let invocations = follow().expand(&[Argument::String("{focused_window}".into())]);
+        assert_eq!(invocations[0].words, vec![Word::text("{focused_window}")]);
+        assert!(invocations[0].words[0].is_quoted);
+    }
+
+    #[test]
+    fn malformed_bodies_are_rejected() {
+        assert!(Macro::new("broken", "", vec![], "focus_on_window: 'unterminated").is_err());
+    }
+
+    #[test]
+    fn missing_arguments_leave_the_word_unchanged() {
+        assert_eq!(
+            texts(&follow().expand(&[]))[0],
+            vec!["move_focused_window_to_workspace_named:", "$workspace"]
+        );
+    }
+}
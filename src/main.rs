@@ -2,10 +2,15 @@
 #![recursion_limit = "128"]
 
 mod artist;
+mod command_parser;
 mod commands;
 mod config;
 mod connection;
 mod layout;
+mod macros;
+mod notification;
+mod overlay;
+mod signals;
 mod window_data;
 mod window_manager;
 mod workspace;
@@ -16,10 +21,17 @@ use structopt::StructOpt;
 struct Args {
     #[structopt(flatten)]
     verbosity: clap_verbosity_flag::Verbosity,
+
+    /// Replace the currently running window manager
+    #[structopt(long = "replace")]
+    replace: bool,
 }
 
 fn main() {
     let args = Args::from_args();
     args.verbosity.setup_env_logger("ceramic").unwrap();
-    window_manager::WindowManager::new(config::Configuration::new()).run();
+    signals::forward_termination_signals_as_quit_command();
+    let exit_code =
+        window_manager::WindowManager::new(config::Configuration::new()).run(args.replace);
+    std::process::exit(exit_code);
 }
@@ -0,0 +1,128 @@
+use super::{artist::Artist, connection::*, layout::*};
+use std::{
+    sync::mpsc::{self, RecvTimeoutError},
+    time::Duration,
+};
+
+// A single helper thread reports the timeout of the latest notification as a client message to
+// the window, which is picked up with the other events. A new notification restarts the timeout.
+pub struct NotificationTimer {
+    sender: mpsc::Sender<u32>,
+}
+
+impl NotificationTimer {
+    pub fn new(window: xcb::Window, duration: Duration) -> NotificationTimer {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            while let Ok(mut serial) = receiver.recv() {
+                loop {
+                    match receiver.recv_timeout(duration) {
+                        Ok(new_serial) => serial = new_serial,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                Self::send_timeout(window, serial);
+            }
+        });
+        NotificationTimer { sender }
+    }
+
+    // The serial identifies the notification the timeout is for
+    pub fn start(&self, serial: u32) {
+        let _ = self.sender.send(serial);
+    }
+
+    fn send_timeout(window: xcb::Window, serial: u32) {
+        let event = xcb::ClientMessageEvent::new(
+            32,
+            window,
+            *ATOM_CERAMIC_NOTIFICATION_TIMEOUT,
+            xcb::ClientMessageData::from_data32([serial, 0, 0, 0, 0]),
+        );
+        // Without an event mask the event goes to the client that created the window
+        xcb::send_event(
+            connection(),
+            false,
+            window,
+            xcb::EVENT_MASK_NO_EVENT,
+            &event,
+        );
+        connection().flush();
+    }
+}
+
+pub struct NotificationArtist {
+    message: String,
+}
+
+impl NotificationArtist {
+    const FONT_FACE: &'static str = "Noto Sans Mono";
+    const FONT_SIZE: u16 = 14;
+
+    const MARGIN: Size = Size::new(12, 8);
+    const TOP_OFFSET: i16 = 40;
+
+    pub fn new(message: &str) -> NotificationArtist {
+        NotificationArtist {
+            message: message.to_owned(),
+        }
+    }
+
+    fn configure_font(&self, context: &cairo::Context) {
+        context.select_font_face(
+            Self::FONT_FACE,
+            cairo::FontSlant::Normal,
+            cairo::FontWeight::Bold,
+        );
+        context.set_font_size(Self::FONT_SIZE as f64);
+    }
+}
+
+impl Artist for NotificationArtist {
+    fn calculate_bounds(&self, window: xcb::Window) -> Option<Bounds> {
+        let screen = screen();
+        if let Ok(surface) = get_cairo_surface(window) {
+            let context = cairo::Context::new(&surface);
+
+            self.configure_font(&context);
+            let font_extents = context.font_extents();
+            let text_extents = context.text_extents(&self.message);
+
+            let width =
+                Self::MARGIN.width + text_extents.x_advance.ceil() as u16 + Self::MARGIN.width;
+            let height =
+                Self::MARGIN.height + font_extents.height.ceil() as u16 + Self::MARGIN.height;
+
+            return Some(Bounds::new(
+                (screen.width_in_pixels() as i16 - width as i16) / 2,
+                Self::TOP_OFFSET,
+                width,
+                height,
+            ));
+        }
+
+        None
+    }
+
+    fn draw(&self, window: xcb::Window) {
+        if let Ok(geometry) = xcb::get_geometry(connection(), window).get_reply() {
+            if let Ok(surface) = get_cairo_surface(window) {
+                let context = cairo::Context::new(&surface);
+
+                context.set_source_rgb(0.6, 0.0, 0.0);
+                context.rectangle(0.0, 0.0, geometry.width() as f64, geometry.height() as f64);
+                context.fill();
+
+                self.configure_font(&context);
+                let font_extents = context.font_extents();
+                context.set_source_rgb(1.0, 1.0, 1.0);
+                context.move_to(
+                    Self::MARGIN.width as f64,
+                    Self::MARGIN.height as f64 + font_extents.ascent,
+                );
+                context.show_text(&self.message);
+            }
+        }
+    }
+}
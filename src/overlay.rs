@@ -0,0 +1,178 @@
+use super::{artist::Artist, connection::*, layout::*};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Overlay {
+    Choices(Vec<(String, String)>), // (label, name)
+    TextEntry { label: String, text: String },
+}
+
+pub struct OverlayArtist {
+    overlay: Overlay,
+}
+
+impl OverlayArtist {
+    const FONT_FACE: &'static str = "Noto Sans Mono";
+    const FONT_SIZE: u16 = 14;
+
+    const MARGIN: Size = Size::new(12, 8);
+    const LABEL_PADDING: Size = Size::new(4, 1);
+    const LABEL_TO_NAME_GAP: u16 = 8;
+    const LINE_SPACING: u16 = 4;
+
+    pub fn new(overlay: &Overlay) -> OverlayArtist {
+        OverlayArtist {
+            overlay: overlay.clone(),
+        }
+    }
+
+    fn configure_label_font(&self, context: &cairo::Context) {
+        context.select_font_face(
+            Self::FONT_FACE,
+            cairo::FontSlant::Normal,
+            cairo::FontWeight::Bold,
+        );
+        context.set_font_size(Self::FONT_SIZE as f64);
+    }
+
+    fn configure_text_font(&self, context: &cairo::Context) {
+        context.select_font_face(
+            Self::FONT_FACE,
+            cairo::FontSlant::Normal,
+            cairo::FontWeight::Normal,
+        );
+        context.set_font_size(Self::FONT_SIZE as f64);
+    }
+
+    fn label_width(&self, context: &cairo::Context, labels: &[&str]) -> u16 {
+        self.configure_label_font(context);
+        labels
+            .iter()
+            .map(|label| context.text_extents(label).x_advance.ceil() as u16)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn text_width(&self, context: &cairo::Context, texts: &[&str]) -> u16 {
+        self.configure_text_font(context);
+        texts
+            .iter()
+            .map(|text| context.text_extents(text).x_advance.ceil() as u16)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn content_size(&self, context: &cairo::Context) -> Size {
+        self.configure_label_font(context);
+        let line_height = context.font_extents().height.ceil() as u16
+            + Self::LABEL_PADDING.height
+            + Self::LABEL_PADDING.height;
+        match &self.overlay {
+            Overlay::Choices(choices) => {
+                let labels = choices.iter().map(|c| c.0.as_str()).collect::<Vec<_>>();
+                let names = choices.iter().map(|c| c.1.as_str()).collect::<Vec<_>>();
+                Size::new(
+                    Self::LABEL_PADDING.width
+                        + self.label_width(context, &labels)
+                        + Self::LABEL_PADDING.width
+                        + Self::LABEL_TO_NAME_GAP
+                        + self.text_width(context, &names),
+                    (choices.len() as u16 * (line_height + Self::LINE_SPACING))
+                        .saturating_sub(Self::LINE_SPACING),
+                )
+            }
+            Overlay::TextEntry { label, text } => Size::new(
+                Self::LABEL_PADDING.width
+                    + self.label_width(context, &[label.as_str()])
+                    + Self::LABEL_PADDING.width
+                    + Self::LABEL_TO_NAME_GAP
+                    + self.text_width(context, &[format!("{}_", text).as_str()]),
+                line_height,
+            ),
+        }
+    }
+
+    fn draw_line(
+        &self,
+        context: &cairo::Context,
+        top: u16,
+        label_width: u16,
+        label: &str,
+        text: &str,
+    ) -> u16 {
+        self.configure_label_font(context);
+        let font_extents = context.font_extents();
+        let line_height = font_extents.height.ceil() as u16;
+        let ascent = font_extents.ascent;
+
+        let label_left = Self::MARGIN.width;
+        let label_right =
+            label_left + Self::LABEL_PADDING.width + label_width + Self::LABEL_PADDING.width;
+        let bottom = top + Self::LABEL_PADDING.height + line_height + Self::LABEL_PADDING.height;
+
+        context.set_source_rgb(0.0, 0.3, 0.6);
+        context.rectangle(
+            label_left as f64,
+            top as f64,
+            (label_right - label_left) as f64,
+            (bottom - top) as f64,
+        );
+        context.fill();
+
+        context.set_source_rgb(1.0, 1.0, 1.0);
+        context.move_to(
+            (label_left + Self::LABEL_PADDING.width) as f64,
+            (top + Self::LABEL_PADDING.height) as f64 + ascent,
+        );
+        context.show_text(label);
+
+        context.set_source_rgb(0.0, 0.0, 0.0);
+        context.move_to(
+            (label_right + Self::LABEL_TO_NAME_GAP) as f64,
+            (top + Self::LABEL_PADDING.height) as f64 + ascent,
+        );
+        self.configure_text_font(context);
+        context.show_text(text);
+
+        bottom
+    }
+}
+
+impl Artist for OverlayArtist {
+    fn calculate_bounds(&self, window: xcb::Window) -> Option<Bounds> {
+        let screen = screen();
+        if let Ok(surface) = get_cairo_surface(window) {
+            let context = cairo::Context::new(&surface);
+            let content_size = self.content_size(&context);
+            let width = Self::MARGIN.width + content_size.width + Self::MARGIN.width;
+            let height = Self::MARGIN.height + content_size.height + Self::MARGIN.height;
+            return Some(Bounds::new(
+                (screen.width_in_pixels() as i16 - width as i16) / 2,
+                (screen.height_in_pixels() as i16 - height as i16) / 2,
+                width,
+                height,
+            ));
+        }
+
+        None
+    }
+
+    fn draw(&self, window: xcb::Window) {
+        if let Ok(surface) = get_cairo_surface(window) {
+            let context = cairo::Context::new(&surface);
+            let top = Self::MARGIN.height;
+            match &self.overlay {
+                Overlay::Choices(choices) => {
+                    let labels = choices.iter().map(|c| c.0.as_str()).collect::<Vec<_>>();
+                    let label_width = self.label_width(&context, &labels);
+                    choices.iter().fold(top, |top, (label, name)| {
+                        self.draw_line(&context, top, label_width, label, name) + Self::LINE_SPACING
+                    });
+                }
+                Overlay::TextEntry { label, text } => {
+                    let label_width = self.label_width(&context, &[label.as_str()]);
+                    self.draw_line(&context, top, label_width, label, &format!("{}_", text));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use super::connection::*;
+
+// Signal handlers can't safely touch the window manager state, so SIGTERM and
+// SIGINT are blocked in every thread and picked up synchronously by a helper
+// thread, which turns them into a `quit` command delivered through the same
+// root window property that ceramic-do.sh uses.
+pub fn forward_termination_signals_as_quit_command() {
+    let signals = unsafe {
+        let mut signals: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut signals);
+        libc::sigaddset(&mut signals, libc::SIGTERM);
+        libc::sigaddset(&mut signals, libc::SIGINT);
+        libc::pthread_sigmask(libc::SIG_BLOCK, &signals, std::ptr::null_mut());
+        signals
+    };
+
+    // Make sure the connection exists before the helper thread can race to create it
+    let root = screen().root();
+
+    std::thread::spawn(move || {
+        let mut signal = 0;
+        if unsafe { libc::sigwait(&signals, &mut signal) } == 0 {
+            log::info!("Received signal {}, quitting", signal);
+            set_string_property(root, *ATOM_CERAMIC_COMMAND, "quit");
+            connection().flush();
+        }
+    });
+}
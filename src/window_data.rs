@@ -1,6 +1,10 @@
-use super::{commands::Commands, connection::*, layout::Bounds};
+use super::{
+    commands::{Argument, ArgumentType, CommandDescriptor, CommandResult, Commands, Parameter},
+    connection::*,
+    layout::Bounds,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct WindowData {
     window: xcb::Window,
     pub is_floating: bool,
@@ -9,12 +13,15 @@ pub struct WindowData {
     pub border_color: (u8, u8, u8),
     pub selector_label: String,
     pub order: Option<i16>,
+    pub tags: Vec<String>, // user-assigned, for routing windows between layouts
+    pub weight: f64,       // share of the space when a layout divides it between windows
 }
 
 impl WindowData {
     pub fn new(window: xcb::Window) -> WindowData {
         WindowData {
             window,
+            weight: 1.0,
             ..Default::default()
         }
     }
@@ -67,22 +74,53 @@ impl WindowData {
 }
 
 impl Commands for WindowData {
-    fn get_commands(&self) -> Vec<String> {
-        vec![String::from("close_focused_window")]
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands = vec![
+            CommandDescriptor::new("close_focused_window", "Close the focused window"),
+            CommandDescriptor::new("add_tag_to_focused_window:", "Tag the focused window")
+                .with_parameter(Parameter::new("tag", ArgumentType::String)),
+        ];
+        if !self.tags.is_empty() {
+            commands.push(
+                CommandDescriptor::new(
+                    "remove_tag_from_focused_window:",
+                    "Remove a tag from the focused window",
+                )
+                .with_parameter(
+                    Parameter::new("tag", ArgumentType::String).with_allowed_values(&self.tags),
+                ),
+            );
+        }
+        commands
     }
 
-    fn execute_command(&mut self, command: &str, _args: &[&str]) -> bool {
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
         match command {
+            "add_tag_to_focused_window:" => match args {
+                [Argument::String(tag)] => {
+                    let is_new = !self.tags.contains(tag);
+                    if is_new {
+                        self.tags.push(tag.clone());
+                    }
+                    CommandResult::changed_if(is_new)
+                }
+                _ => CommandResult::invalid_arguments(command),
+            },
+            "remove_tag_from_focused_window:" => match args {
+                [Argument::String(tag)] => {
+                    let number_of_tags = self.tags.len();
+                    self.tags.retain(|t| t != tag);
+                    CommandResult::changed_if(self.tags.len() != number_of_tags)
+                }
+                _ => CommandResult::invalid_arguments(command),
+            },
             "close_focused_window" => {
                 // This isn't the correct call
                 // xcb::kill_client(&connection(), self.window);
                 // destruction of window will trigger layout update
-                false
-            }
-            _ => {
-                eprintln!("Unhandled command: {}", command);
-                false
+                CommandResult::Unchanged
             }
+            _ => CommandResult::unknown_command(command),
         }
     }
 }
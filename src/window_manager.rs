@@ -1,45 +1,116 @@
 use super::{
     artist::Artist,
-    commands::Commands,
+    command_parser::{self, Invocation, Word, WordPart},
+    commands::{
+        catalog_to_json, extend_commands, Argument, ArgumentType, CommandDescriptor, CommandResult,
+        Commands, Parameter,
+    },
     config::ConfigurationProvider,
     connection::*,
-    layout::{Bounds, Position},
+    layout::{add_window_selector_labels::SELECTOR_LABELS, Bounds, LayoutSettings, Position},
+    macros::Macro,
+    notification::{NotificationArtist, NotificationTimer},
+    overlay::{Overlay, OverlayArtist},
     workspace::Workspace,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 pub struct WindowManager {
     configuration: Box<dyn ConfigurationProvider>,
     workspaces: Vec<Workspace>,
     current_workspace: usize,
+    workspace_history: Vec<String>, // names of previously current workspaces, most recent last
+    macros: Vec<Macro>,
+    macro_depth: usize,
     unmanaged_windows: Vec<xcb::Window>,
     decorations: HashMap<xcb::Window, Box<dyn Artist>>,
+    check_window: xcb::Window,
+    exit_code: Option<i32>,
+    is_replaced: bool,
+    notification: Option<String>,
+    notification_serial: u32, // identifies the notification a timeout is for
+    notification_timer: Option<NotificationTimer>, // started with the first notification
+    overlay: Option<Overlay>,
+    layout_settings: LayoutSettings,
+    published_commands: Vec<CommandDescriptor>, // as set on the root window
 }
 
 impl WindowManager {
     pub fn new(configuration: Box<dyn ConfigurationProvider>) -> WindowManager {
-        let workspaces = configuration.workspaces();
+        let mut workspaces = configuration.workspaces();
+        if workspaces.is_empty() {
+            log::error!("No workspaces are configured");
+            workspaces.push(configuration.workspace("1"));
+        }
+        let macros = configuration
+            .macros()
+            .into_iter()
+            .filter_map(|result| result.map_err(|message| log::error!("{}", message)).ok())
+            .collect();
         WindowManager {
             configuration,
             workspaces,
             current_workspace: Default::default(),
+            workspace_history: Default::default(),
+            macros,
+            macro_depth: 0,
             unmanaged_windows: Default::default(),
             decorations: Default::default(),
+            check_window: xcb::NONE,
+            exit_code: None,
+            is_replaced: false,
+            notification: None,
+            notification_serial: 0,
+            notification_timer: None,
+            overlay: None,
+            layout_settings: Default::default(),
+            published_commands: Default::default(),
         }
     }
 
-    pub fn run(&mut self) {
+    pub fn run(&mut self, replace: bool) -> i32 {
         let connection = connection();
-        let screen = connection.get_setup().roots().nth(0).unwrap();
-        let values = [(
-            xcb::CW_EVENT_MASK,
-            xcb::EVENT_MASK_SUBSTRUCTURE_NOTIFY
-                | xcb::EVENT_MASK_SUBSTRUCTURE_REDIRECT
-                | xcb::EVENT_MASK_PROPERTY_CHANGE,
-        )];
-        xcb::change_window_attributes_checked(connection, screen.root(), &values)
-            .request_check()
-            .expect("Cannot install as window manager");
+        let screen = screen();
+
+        self.create_check_window();
+        if let Err(message) = self.acquire_manager_selection(replace) {
+            log::error!("{}", message);
+            return 1;
+        }
+
+        let event_mask = xcb::EVENT_MASK_SUBSTRUCTURE_NOTIFY
+            | xcb::EVENT_MASK_SUBSTRUCTURE_REDIRECT
+            | xcb::EVENT_MASK_PROPERTY_CHANGE;
+        if xcb::change_window_attributes_checked(
+            connection,
+            screen.root(),
+            &[(xcb::CW_EVENT_MASK, event_mask)],
+        )
+        .request_check()
+        .is_err()
+        {
+            log::error!("Cannot install as window manager - another window manager is running");
+            return 1;
+        }
+
+        // Only one client may select button presses on the root window, so another client
+        // holding them just costs the boundary dragging
+        let button_event_mask = xcb::EVENT_MASK_BUTTON_PRESS
+            | xcb::EVENT_MASK_BUTTON_RELEASE
+            | xcb::EVENT_MASK_BUTTON_1_MOTION;
+        if xcb::change_window_attributes_checked(
+            connection,
+            screen.root(),
+            &[(xcb::CW_EVENT_MASK, event_mask | button_event_mask)],
+        )
+        .request_check()
+        .is_err()
+        {
+            log::warn!(
+                "Cannot select button events on the root window - another client has them, so \
+                 the boundaries between tiles cannot be dragged"
+            );
+        }
         self.set_initial_root_window_properties();
 
         for w in xcb::query_tree(connection, screen.root())
@@ -53,23 +124,38 @@ impl WindowManager {
         self.workspaces[self.current_workspace].show();
 
         self.run_default_event_loop();
+
+        match self.exit_code {
+            Some(exit_code) => {
+                self.shutdown();
+                exit_code
+            }
+            None => {
+                log::error!("Lost connection to the X server");
+                1
+            }
+        }
     }
 
-    pub fn do_command(&mut self, command: &str, args: &[&str]) {
+    pub fn do_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
         // eprintln!("execute command: {} {:?}", command, args);
-        if self.execute_command(command, args) {
-            self.update_layout();
+        let result = self.execute_command(command, args);
+        match &result {
+            CommandResult::Changed => self.update_layout(),
+            CommandResult::Unchanged => (),
+            CommandResult::Error(message) => log::error!("{}", message),
         }
+        result
     }
 
-    fn set_initial_root_window_properties(&self) {
+    fn create_check_window(&mut self) {
         let connection = connection();
-        let screen = connection.get_setup().roots().nth(0).unwrap();
-        let check_window_id = connection.generate_id();
+        let screen = screen();
+        self.check_window = connection.generate_id();
         xcb::create_window(
             connection,
             xcb::COPY_FROM_PARENT as u8,
-            check_window_id,
+            self.check_window,
             screen.root(),
             -100,
             -100,
@@ -78,8 +164,79 @@ impl WindowManager {
             0,
             xcb::WINDOW_CLASS_INPUT_OUTPUT as u16,
             screen.root_visual(),
-            &[(xcb::CW_OVERRIDE_REDIRECT, 1)],
+            &[
+                (xcb::CW_OVERRIDE_REDIRECT, 1),
+                (xcb::CW_EVENT_MASK, xcb::EVENT_MASK_PROPERTY_CHANGE),
+            ],
+        );
+        connection.flush();
+    }
+
+    const REPLACE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    // See ICCCM section 2.8 - the check window doubles as the manager selection owner
+    fn acquire_manager_selection(&mut self, replace: bool) -> Result<(), String> {
+        let connection = connection();
+        let screen = screen();
+
+        let current_owner = xcb::get_selection_owner(connection, *ATOM_WM_SN)
+            .get_reply()
+            .map(|reply| reply.owner())
+            .unwrap_or(xcb::NONE);
+        if current_owner != xcb::NONE {
+            if !replace {
+                return Err(String::from(
+                    "Another window manager is running - use --replace to take over",
+                ));
+            }
+            xcb::change_window_attributes(
+                connection,
+                current_owner,
+                &[(xcb::CW_EVENT_MASK, xcb::EVENT_MASK_STRUCTURE_NOTIFY)],
+            );
+        }
+
+        let timestamp = get_timestamp(self.check_window);
+        xcb::set_selection_owner(connection, self.check_window, *ATOM_WM_SN, timestamp);
+        let new_owner = xcb::get_selection_owner(connection, *ATOM_WM_SN)
+            .get_reply()
+            .map(|reply| reply.owner())
+            .unwrap_or(xcb::NONE);
+        if new_owner != self.check_window {
+            return Err(String::from("Cannot acquire the window manager selection"));
+        }
+
+        if current_owner != xcb::NONE {
+            log::info!("Waiting for the current window manager to exit");
+            if !wait_for_window_destruction(current_owner, Self::REPLACE_TIMEOUT) {
+                return Err(String::from(
+                    "Timed out waiting for the current window manager to exit",
+                ));
+            }
+        }
+
+        let event = xcb::ClientMessageEvent::new(
+            32,
+            screen.root(),
+            *ATOM_MANAGER,
+            xcb::ClientMessageData::from_data32([timestamp, *ATOM_WM_SN, self.check_window, 0, 0]),
+        );
+        xcb::send_event(
+            connection,
+            false,
+            screen.root(),
+            xcb::EVENT_MASK_STRUCTURE_NOTIFY,
+            &event,
         );
+        connection.flush();
+
+        Ok(())
+    }
+
+    fn set_initial_root_window_properties(&self) {
+        let connection = connection();
+        let screen = screen();
+        let check_window_id = self.check_window;
         set_string_property(check_window_id, *ATOM__NET_WM_NAME, "ceramic");
         set_window_property(
             check_window_id,
@@ -91,21 +248,7 @@ impl WindowManager {
             *ATOM__NET_SUPPORTING_WM_CHECK,
             check_window_id,
         );
-        set_cardinal_property(screen.root(), *ATOM__NET_CURRENT_DESKTOP, 0);
-        set_cardinal_property(
-            screen.root(),
-            *ATOM__NET_NUMBER_OF_DESKTOPS,
-            self.workspaces.len() as u32,
-        );
-        set_strings_property(
-            screen.root(),
-            *ATOM__NET_DESKTOP_NAMES,
-            &self
-                .workspaces
-                .iter()
-                .map(|ws| ws.name.as_str())
-                .collect::<Vec<_>>(),
-        );
+        self.set_root_window_desktop_properties();
         set_atoms_property(
             screen.root(),
             *ATOM__NET_SUPPORTED,
@@ -123,19 +266,105 @@ impl WindowManager {
         connection.flush();
     }
 
+    fn set_root_window_desktop_properties(&self) {
+        let screen = screen();
+        set_cardinal_property(
+            screen.root(),
+            *ATOM__NET_CURRENT_DESKTOP,
+            self.current_workspace as u32,
+        );
+        set_cardinal_property(
+            screen.root(),
+            *ATOM__NET_NUMBER_OF_DESKTOPS,
+            self.workspaces.len() as u32,
+        );
+        set_strings_property(
+            screen.root(),
+            *ATOM__NET_DESKTOP_NAMES,
+            &self.workspace_names(),
+        );
+    }
+
+    // Lets the loops that grab input for a while end as soon as a quit has been requested
+    fn wait_for_event_unless_quitting(&self) -> Option<xcb::base::GenericEvent> {
+        if self.exit_code.is_some() {
+            None
+        } else {
+            wait_for_event()
+        }
+    }
+
     fn run_default_event_loop(&mut self) {
-        while let Some(e) = wait_for_event() {
-            self.dispatch_wm_event(&e);
+        while self.exit_code.is_none() {
+            match wait_for_event() {
+                Some(e) => self.dispatch_wm_event(&e),
+                None => return,
+            }
         }
     }
 
+    fn shutdown(&mut self) {
+        log::debug!("Shutting down");
+
+        let connection = connection();
+        let screen = screen();
+
+        // Leave every window visible so that nothing is stranded on a hidden workspace
+        for workspace in &self.workspaces {
+            for window_data in &workspace.windows {
+                xcb::map_window(connection, window_data.window());
+            }
+        }
+
+        for window in self.decorations.keys().copied() {
+            xcb::destroy_window(connection, window);
+        }
+        self.decorations.clear();
+
+        // A replacing window manager owns the root window properties and focus by now
+        if !self.is_replaced {
+            for atom in &[
+                *ATOM__NET_SUPPORTING_WM_CHECK,
+                *ATOM__NET_SUPPORTED,
+                *ATOM__NET_NUMBER_OF_DESKTOPS,
+                *ATOM__NET_DESKTOP_NAMES,
+                *ATOM__NET_CURRENT_DESKTOP,
+                *ATOM__NET_ACTIVE_WINDOW,
+                *ATOM_CERAMIC_AVAILABLE_COMMANDS,
+                *ATOM_CERAMIC_COMMAND_CATALOG,
+                *ATOM_CERAMIC_COMMAND_RESULT,
+            ] {
+                xcb::delete_property(connection, screen.root(), *atom);
+            }
+
+            xcb::set_input_focus(
+                connection,
+                xcb::INPUT_FOCUS_POINTER_ROOT as u8,
+                xcb::INPUT_FOCUS_POINTER_ROOT,
+                xcb::CURRENT_TIME,
+            );
+        }
+
+        // Release substructure redirection before the selection owner disappears, because
+        // that is the signal for a replacing window manager to install itself
+        xcb::change_window_attributes(
+            connection,
+            screen.root(),
+            &[(xcb::CW_EVENT_MASK, xcb::EVENT_MASK_NO_EVENT)],
+        );
+        xcb::destroy_window(connection, self.check_window);
+        self.check_window = xcb::NONE;
+
+        connection.flush();
+    }
+
     fn run_keygrab_event_loop(&mut self) -> Option<String> {
         log::debug!("Enter grab loop");
         let mut key_press_count = 0;
         let mut selected_label: Option<String> = None;
         grab_keyboard();
         let key_symbols = xcb_util::keysyms::KeySymbols::new(connection());
-        while let Some(e) = wait_for_event() {
+        while let Some(e) = self.wait_for_event_unless_quitting() {
             match e.response_type() & 0x7f {
                 xcb::KEY_PRESS => {
                     let press_event: &xcb::KeyPressEvent = unsafe { xcb::cast_event(&e) };
@@ -172,7 +401,140 @@ impl WindowManager {
         }
         ungrab_keyboard();
         log::debug!("Exit grab loop with {:?}", selected_label);
-        selected_label
+        selected_label.filter(|_| self.exit_code.is_none())
+    }
+
+    fn run_choice_event_loop(&mut self, names: Vec<String>) -> Option<String> {
+        let choices = SELECTOR_LABELS
+            .chars()
+            .map(|c| c.to_string())
+            .zip(names)
+            .collect::<Vec<_>>();
+        self.overlay = Some(Overlay::Choices(choices.clone()));
+        self.update_layout();
+        connection().flush();
+
+        let selected_label = self.run_keygrab_event_loop();
+
+        self.overlay = None;
+        self.update_layout();
+        selected_label.and_then(|label| {
+            choices
+                .into_iter()
+                .find(|(l, _)| *l == label)
+                .map(|(_, name)| name)
+        })
+    }
+
+    const KEYSYM_BACKSPACE: xcb::Keysym = 0xff08;
+    const KEYSYM_RETURN: xcb::Keysym = 0xff0d;
+    const KEYSYM_ESCAPE: xcb::Keysym = 0xff1b;
+    const KEYSYM_KP_ENTER: xcb::Keysym = 0xff8d;
+
+    fn run_text_entry_event_loop(&mut self, label: &str) -> Option<String> {
+        log::debug!("Enter text entry loop");
+        let mut text = String::new();
+        let mut result = None;
+        self.overlay = Some(Overlay::TextEntry {
+            label: label.to_owned(),
+            text: text.clone(),
+        });
+        self.update_layout();
+        grab_keyboard();
+        let key_symbols = xcb_util::keysyms::KeySymbols::new(connection());
+        while let Some(e) = self.wait_for_event_unless_quitting() {
+            match e.response_type() & 0x7f {
+                xcb::KEY_PRESS => {
+                    let press_event: &xcb::KeyPressEvent = unsafe { xcb::cast_event(&e) };
+                    let column = if press_event.state() & xcb::MOD_MASK_SHIFT as u16 != 0 {
+                        1
+                    } else {
+                        0
+                    };
+                    match key_symbols.get_keysym(press_event.detail(), column) {
+                        Self::KEYSYM_RETURN | Self::KEYSYM_KP_ENTER => {
+                            result = Some(text);
+                            break;
+                        }
+                        Self::KEYSYM_ESCAPE => break,
+                        Self::KEYSYM_BACKSPACE => {
+                            text.pop();
+                        }
+                        // Latin-1 keysyms are the same as their code points
+                        keysym @ 0x20..=0x7e | keysym @ 0xa0..=0xff => {
+                            text.extend(std::char::from_u32(keysym))
+                        }
+                        _ => continue,
+                    }
+                    self.overlay = Some(Overlay::TextEntry {
+                        label: label.to_owned(),
+                        text: text.clone(),
+                    });
+                    self.update_layout();
+                    connection().flush();
+                }
+                xcb::KEY_RELEASE => (),
+                _ => self.dispatch_wm_event(&e),
+            }
+        }
+        ungrab_keyboard();
+        self.overlay = None;
+        self.update_layout();
+        log::debug!("Exit text entry loop with {:?}", result);
+        result.filter(|_| self.exit_code.is_none())
+    }
+
+    const CROSSHAIR_CURSOR: u16 = 34;
+    const CANCEL_BUTTON: xcb::Button = 3;
+
+    fn run_click_event_loop(&mut self) -> Option<xcb::Window> {
+        if !grab_pointer_with_cursor(Self::CROSSHAIR_CURSOR) {
+            return None;
+        }
+        grab_keyboard();
+        let key_symbols = xcb_util::keysyms::KeySymbols::new(connection());
+        let mut clicked_window = None;
+        while let Some(e) = self.wait_for_event_unless_quitting() {
+            match e.response_type() & 0x7f {
+                xcb::BUTTON_PRESS => {
+                    let e: &xcb::ButtonPressEvent = unsafe { xcb::cast_event(&e) };
+                    // Any button but the right one, which cancels
+                    if e.detail() != Self::CANCEL_BUTTON {
+                        clicked_window = Some(e.child());
+                    }
+                    break;
+                }
+                xcb::KEY_PRESS => {
+                    let e: &xcb::KeyPressEvent = unsafe { xcb::cast_event(&e) };
+                    if key_symbols.get_keysym(e.detail(), 0) == Self::KEYSYM_ESCAPE {
+                        break;
+                    }
+                }
+                xcb::KEY_RELEASE => (),
+                _ => self.dispatch_wm_event(&e),
+            }
+        }
+        ungrab_keyboard();
+        ungrab_pointer();
+        clicked_window.filter(|window| {
+            self.exit_code.is_none() && self.is_managed_in_current_workspace(*window)
+        })
+    }
+
+    fn window_under_pointer(&self) -> Option<xcb::Window> {
+        let screen = screen();
+        xcb::query_pointer(connection(), screen.root())
+            .get_reply()
+            .ok()
+            .map(|reply| reply.child())
+            .filter(|window| self.is_managed_in_current_workspace(*window))
+    }
+
+    fn is_managed_in_current_workspace(&self, window: xcb::Window) -> bool {
+        self.workspaces[self.current_workspace]
+            .windows
+            .iter()
+            .any(|w| w.window() == window)
     }
 
     const MINIMUM_RESIZE_WIDTH: u16 = 20;
@@ -196,9 +558,9 @@ impl WindowManager {
         };
         let mouse_down = Position::new(e.root_x(), e.root_y());
 
-        self.do_command("float_window:", &[format!("{}", window).as_str()]);
+        self.do_command("float_window:", &[Argument::Window(window)]);
 
-        while let Some(e) = wait_for_event() {
+        while let Some(e) = self.wait_for_event_unless_quitting() {
             match e.response_type() & 0x7f {
                 xcb::BUTTON_RELEASE => {
                     break;
@@ -224,6 +586,48 @@ impl WindowManager {
         }
     }
 
+    const BOUNDARY_TOLERANCE: u16 = 8;
+
+    fn run_boundary_drag_event_loop(&mut self, e: &xcb::ButtonPressEvent) {
+        let mouse_down = Position::new(e.root_x(), e.root_y());
+        let boundary = match self.workspaces[self.current_workspace]
+            .get_boundaries(&self.layout_bounds())
+            .into_iter()
+            .find(|b| b.is_near(mouse_down, Self::BOUNDARY_TOLERANCE))
+        {
+            Some(boundary) => boundary,
+            None => return,
+        };
+        let command = format!("{}set_ratio:", boundary.command_prefix);
+        let workspace_name = self.workspaces[self.current_workspace].name.clone();
+
+        self.workspaces[self.current_workspace].begin_gesture();
+        while let Some(e) = self.wait_for_event_unless_quitting() {
+            match e.response_type() & 0x7f {
+                xcb::BUTTON_RELEASE => {
+                    break;
+                }
+
+                xcb::MOTION_NOTIFY => {
+                    let e: &xcb::MotionNotifyEvent = unsafe { xcb::cast_event(&e) };
+                    let ratio = boundary.ratio_at(Position::new(e.root_x(), e.root_y()));
+                    let thousandths = (ratio * 1000.0).round() as i64;
+                    self.do_command(&command, &[Argument::Number(thousandths)]);
+                    connection().flush();
+                }
+                _ => self.dispatch_wm_event(&e),
+            }
+        }
+        // Other events may have switched or deleted the workspace meanwhile
+        if let Some(workspace) = self
+            .workspaces
+            .iter_mut()
+            .find(|ws| ws.name == workspace_name)
+        {
+            workspace.end_gesture();
+        }
+    }
+
     fn run_window_resize_event_loop(&mut self, e: &xcb::ButtonPressEvent) {
         // TODO: lock out commands?
         let window = e.event();
@@ -231,7 +635,7 @@ impl WindowManager {
         let mut x = e.root_x();
         let mut y = e.root_y();
 
-        self.do_command("float_window:", &[format!("{}", window).as_str()]);
+        self.do_command("float_window:", &[Argument::Window(window)]);
 
         let mut adjust_origin_x = 0;
         let mut adjust_origin_y = 0;
@@ -257,7 +661,7 @@ impl WindowManager {
             }
         }
 
-        while let Some(e) = wait_for_event() {
+        while let Some(e) = self.wait_for_event_unless_quitting() {
             match e.response_type() & 0x7f {
                 xcb::BUTTON_RELEASE => {
                     break;
@@ -312,8 +716,20 @@ impl WindowManager {
             xcb::BUTTON_PRESS => {
                 let e: &xcb::ButtonPressEvent = unsafe { xcb::cast_event(e) };
 
-                if e.state() == 0 {
-                    self.do_command("focus_on_window:", &[format!("{}", e.event()).as_str()]);
+                let decoration = self
+                    .decorations
+                    .get(&e.event())
+                    .map(|artist| artist.window_at(Position::new(e.event_x(), e.event_y())));
+                let root = screen().root();
+                if e.event() == root && e.child() == xcb::NONE {
+                    // Clicks on the root window itself are in the gaps between tiles
+                    self.run_boundary_drag_event_loop(e);
+                } else if let Some(clicked_window) = decoration {
+                    if let Some(window) = clicked_window {
+                        self.do_command("focus_on_window:", &[Argument::Window(window)]);
+                    }
+                } else if e.state() == 0 {
+                    self.do_command("focus_on_window:", &[Argument::Window(e.event())]);
                     xcb::ungrab_pointer(connection(), xcb::CURRENT_TIME);
                     xcb::send_event(
                         &connection(),
@@ -347,6 +763,12 @@ impl WindowManager {
                     let command = get_string_property(e.window(), e.atom());
                     xcb::delete_property(&connection(), e.window(), e.atom());
                     self.parse_and_dispatch_command(command.as_str());
+                } else if e.atom() == *ATOM__NET_WM_NAME
+                    && self.is_managed_in_current_workspace(e.window())
+                {
+                    // Layouts may route windows by title, and decorations such as tab bars
+                    // show it
+                    self.update_layout();
                 }
             }
 
@@ -404,10 +826,28 @@ impl WindowManager {
                 }
             }
 
-            xcb::CLIENT_MESSAGE
-            | xcb::CREATE_NOTIFY
-            | xcb::CONFIGURE_NOTIFY
-            | xcb::MAPPING_NOTIFY => (),
+            xcb::SELECTION_CLEAR => {
+                let e: &xcb::SelectionClearEvent = unsafe { xcb::cast_event(e) };
+
+                if e.selection() == *ATOM_WM_SN {
+                    log::info!("Another window manager is replacing ceramic");
+                    self.is_replaced = true;
+                    self.exit_code = Some(0);
+                }
+            }
+
+            xcb::CLIENT_MESSAGE => {
+                let e: &xcb::ClientMessageEvent = unsafe { xcb::cast_event(e) };
+                // Later notifications have their own timeout
+                if e.type_() == *ATOM_CERAMIC_NOTIFICATION_TIMEOUT
+                    && e.data().data32()[0] == self.notification_serial
+                    && self.notification.take().is_some()
+                {
+                    self.update_layout();
+                }
+            }
+
+            xcb::CREATE_NOTIFY | xcb::CONFIGURE_NOTIFY | xcb::MAPPING_NOTIFY => (),
 
             _ => (), //eprintln!("UNEXPECTED EVENT TYPE: {}", e.response_type()),
         }
@@ -415,8 +855,9 @@ impl WindowManager {
         connection().flush();
     }
 
-    fn update_layout(&mut self) {
-        let screen = connection().get_setup().roots().nth(0).unwrap();
+    // The screen less the space reserved by docks and panels
+    fn layout_bounds(&self) -> Bounds {
+        let screen = screen();
         let mut bounds = Bounds::new(0, 0, screen.width_in_pixels(), screen.height_in_pixels());
 
         for window in &self.unmanaged_windows {
@@ -433,19 +874,33 @@ impl WindowManager {
             }
         }
 
-        let artists = self.workspaces[self.current_workspace].update_layout(&bounds);
+        bounds
+    }
+
+    fn update_layout(&mut self) {
+        let bounds = self.layout_bounds();
+        let mut artists = self.workspaces[self.current_workspace].update_layout(&bounds);
+        if let Some(message) = &self.notification {
+            artists.push(Box::new(NotificationArtist::new(message)));
+        }
+        if let Some(overlay) = &self.overlay {
+            artists.push(Box::new(OverlayArtist::new(overlay)));
+        }
         self.set_artists(artists);
         self.set_root_window_available_commands_property();
     }
 
     fn set_artists(&mut self, artists: Vec<Box<dyn Artist>>) {
-        let screen = connection().get_setup().roots().nth(0).unwrap();
+        let screen = screen();
         let root = screen.root();
         let root_visual = screen.root_visual();
 
         let values = [
             (xcb::CW_BACK_PIXEL, screen.white_pixel()),
-            (xcb::CW_EVENT_MASK, xcb::EVENT_MASK_EXPOSURE),
+            (
+                xcb::CW_EVENT_MASK,
+                xcb::EVENT_MASK_EXPOSURE | xcb::EVENT_MASK_BUTTON_PRESS,
+            ),
             (xcb::CW_OVERRIDE_REDIRECT, 1),
         ];
 
@@ -494,74 +949,450 @@ impl WindowManager {
         }
     }
 
-    fn set_workspace(&mut self, workspace: usize) -> bool {
+    fn set_workspace(&mut self, workspace: usize) -> CommandResult {
         if workspace != self.current_workspace {
+            let previous_name = self.workspaces[self.current_workspace].name.clone();
+            let name = self.workspaces[workspace].name.clone();
+            self.workspace_history
+                .retain(|n| *n != previous_name && *n != name);
+            self.workspace_history.push(previous_name);
+
             self.workspaces[self.current_workspace].hide();
             self.current_workspace = workspace;
             self.workspaces[self.current_workspace].show();
-            let screen = connection().get_setup().roots().nth(0).unwrap();
+            let screen = screen();
             set_cardinal_property(
                 screen.root(),
                 *ATOM__NET_CURRENT_DESKTOP,
                 self.current_workspace as u32,
             );
             connection().flush();
-            true
+            CommandResult::Changed
         } else {
-            false
+            CommandResult::Unchanged
         }
     }
 
-    fn set_root_window_available_commands_property(&self) {
-        let screen = connection().get_setup().roots().nth(0).unwrap();
+    // Wraps around, and None if there is no other workspace to switch to
+    fn workspace_in_order(&self, is_forward: bool) -> Option<usize> {
+        let number_of_workspaces = self.workspaces.len();
+        let skips_empty_workspaces = self.configuration.skip_empty_workspaces();
+        (1..number_of_workspaces)
+            .map(|i| {
+                if is_forward {
+                    (self.current_workspace + i) % number_of_workspaces
+                } else {
+                    (self.current_workspace + number_of_workspaces - i) % number_of_workspaces
+                }
+            })
+            .find(|index| !skips_empty_workspaces || !self.workspaces[*index].windows.is_empty())
+    }
+
+    fn previous_workspace(&self) -> Option<usize> {
+        self.workspace_history
+            .last()
+            .and_then(|name| self.workspaces.iter().position(|ws| ws.name == *name))
+    }
+
+    // The properties are only rewritten when the commands change, which most layouts don't
+    fn set_root_window_available_commands_property(&mut self) {
+        let commands = self.get_commands();
+        if commands == self.published_commands {
+            return;
+        }
+        let screen = screen();
         set_strings_property(
             screen.root(),
             *ATOM_CERAMIC_AVAILABLE_COMMANDS,
-            &self
-                .get_commands()
+            &commands
                 .iter()
-                .map(|s| s.as_str())
+                .map(|c| c.name.as_str())
                 .collect::<Vec<&str>>(),
         );
+        set_string_property(
+            screen.root(),
+            *ATOM_CERAMIC_COMMAND_CATALOG,
+            &catalog_to_json(&commands),
+        );
+        self.published_commands = commands;
     }
 
     fn parse_and_dispatch_command(&mut self, command_string: &str) {
-        let mut tokens = command_string.split(' ');
-        if let Some(command) = tokens.next() {
-            if let Ok(args) = tokens
-                .map(|token| match token {
-                    "{selected_window}" => {
-                        self.do_command("layout/show_window_selector_labels", &[]);
-                        let selected_label = self.run_keygrab_event_loop();
-                        self.do_command("layout/hide_window_selector_labels", &[]);
-                        selected_label
-                            .and_then(|label| {
-                                self.workspaces[self.current_workspace]
-                                    .windows
-                                    .iter()
-                                    .find(|w| w.selector_label == label)
-                                    .map(|w| format!("{}", w.window()))
-                            })
-                            .ok_or(())
+        // Any notification from the previous command is dismissed by the next one
+        let mut needs_layout = self.notification.take().is_some();
+        let mut is_changed = false;
+        let mut error = None;
+
+        // The whole chain shares a single layout update
+        match command_parser::parse(command_string) {
+            Ok(invocations) => match self.execute_invocations(&invocations) {
+                CommandResult::Changed => is_changed = true,
+                CommandResult::Unchanged => (),
+                CommandResult::Error(message) => error = Some(message),
+            },
+            Err(message) => error = Some(format!("Cannot parse command: {}", message)),
+        }
+
+        let result = match error {
+            Some(message) => {
+                log::error!("{}: {}", command_string, message);
+                self.show_notification(&message);
+                CommandResult::Error(message)
+            }
+            None => CommandResult::changed_if(is_changed),
+        };
+        self.set_root_window_command_result_property(&result);
+
+        needs_layout |= is_changed || self.notification.is_some();
+        if needs_layout {
+            self.update_layout();
+        }
+    }
+
+    const NOTIFICATION_DURATION: Duration = Duration::from_secs(5);
+
+    fn show_notification(&mut self, message: &str) {
+        self.notification = Some(message.to_owned());
+        self.notification_serial = self.notification_serial.wrapping_add(1);
+
+        let check_window = self.check_window;
+        self.notification_timer
+            .get_or_insert_with(|| {
+                NotificationTimer::new(check_window, Self::NOTIFICATION_DURATION)
+            })
+            .start(self.notification_serial);
+    }
+
+    // Stops at the first failure, in which case earlier commands may still have made changes
+    fn execute_invocations(&mut self, invocations: &[Invocation]) -> CommandResult {
+        let mut is_changed = false;
+        for invocation in invocations {
+            // Nothing more is done once quitting, e.g. after a prompt was ended by a quit
+            if self.exit_code.is_some() {
+                break;
+            }
+            let result = match self.bind_arguments(invocation) {
+                Ok(args) => self.execute_command(&invocation.command, &args),
+                Err(message) => CommandResult::Error(message),
+            };
+            match result {
+                CommandResult::Changed => is_changed = true,
+                CommandResult::Unchanged => (),
+                CommandResult::Error(_) => return result,
+            }
+        }
+        CommandResult::changed_if(is_changed)
+    }
+
+    const MAXIMUM_MACRO_DEPTH: usize = 16;
+
+    fn execute_macro(&mut self, index: usize, args: &[Argument]) -> CommandResult {
+        if self.macro_depth == Self::MAXIMUM_MACRO_DEPTH {
+            return CommandResult::Error(format!(
+                "Macros nested more than {} deep",
+                Self::MAXIMUM_MACRO_DEPTH
+            ));
+        }
+        let invocations = self.macros[index].expand(args);
+        self.macro_depth += 1;
+        let result = self.execute_invocations(&invocations);
+        self.macro_depth -= 1;
+        result
+    }
+
+    fn set_root_window_command_result_property(&self, result: &CommandResult) {
+        let screen = screen();
+        let values = match result {
+            CommandResult::Changed => vec!["changed"],
+            CommandResult::Unchanged => vec!["unchanged"],
+            CommandResult::Error(message) => vec!["error", message.as_str()],
+        };
+        set_strings_property(screen.root(), *ATOM_CERAMIC_COMMAND_RESULT, &values);
+    }
+
+    // Commands addressed to a specific workspace aren't published, so they are looked up
+    // by routing the path to that workspace
+    fn find_command(&self, command: &str) -> Option<CommandDescriptor> {
+        match self.split_workspace_path(command) {
+            Some((index, rest)) => {
+                let mut commands = self.get_workspace_commands(index);
+                extend_commands(&mut commands, self.workspaces[index].get_all_commands());
+                commands
+                    .into_iter()
+                    .find(|c| c.name == rest)
+                    .map(|c| c.prefixed(&format!("workspace/{}/", self.workspaces[index].name)))
+            }
+            None => self
+                .get_all_commands()
+                .into_iter()
+                .find(|c| c.name == command),
+        }
+    }
+
+    fn split_workspace_path<'a>(&self, command: &'a str) -> Option<(usize, &'a str)> {
+        if !command.starts_with("workspace/") {
+            return None;
+        }
+        let path = command.split_at(10).1;
+        let separator = path.find('/')?;
+        let (name, rest) = (&path[..separator], &path[separator + 1..]);
+        self.workspaces
+            .iter()
+            .position(|ws| ws.name == name)
+            .map(|index| (index, rest))
+    }
+
+    fn get_workspace_commands(&self, index: usize) -> Vec<CommandDescriptor> {
+        let mut commands = self.workspaces[index].get_commands();
+        if self.workspaces.len() > 1 {
+            commands.push(
+                CommandDescriptor::new(
+                    "move_focused_window_to_workspace_named:",
+                    "Move the focused window to another workspace",
+                )
+                .with_parameter(
+                    Parameter::new("workspace", ArgumentType::String)
+                        .with_allowed_values(&self.workspace_names()),
+                ),
+            );
+        }
+        commands.push(
+            CommandDescriptor::new(
+                "move_workspace_to_index:",
+                "Move the workspace to another position, counting from 0",
+            )
+            .with_parameter(Parameter::new("index", ArgumentType::Number)),
+        );
+        commands.push(
+            CommandDescriptor::new("rename_workspace:", "Rename the workspace")
+                .with_parameter(Parameter::new("name", ArgumentType::String)),
+        );
+        commands
+    }
+
+    fn execute_workspace_command(
+        &mut self,
+        index: usize,
+        command: &str,
+        args: &[Argument],
+    ) -> CommandResult {
+        match (command, args) {
+            ("move_focused_window_to_workspace_named:", [Argument::String(name)]) => {
+                match self.workspaces.iter().position(|ws| ws.name == *name) {
+                    Some(new_workspace) if new_workspace != index => {
+                        if let Some(window_data) = self.workspaces[index].remove_focused_window() {
+                            if new_workspace == self.current_workspace {
+                                xcb::map_window(connection(), window_data.window());
+                            } else {
+                                xcb::unmap_window(connection(), window_data.window());
+                            }
+                            self.workspaces[new_workspace].add_window_data(window_data);
+                            CommandResult::Changed
+                        } else {
+                            CommandResult::error("No window focused")
+                        }
+                    }
+                    Some(_) => CommandResult::Unchanged,
+                    None => CommandResult::Error(format!("Unknown workspace: {}", name)),
+                }
+            }
+            ("move_workspace_to_index:", [Argument::Number(new_index)]) => {
+                if *new_index < 0 || *new_index as usize >= self.workspaces.len() {
+                    CommandResult::Error(format!(
+                        "Workspace index must be between 0 and {}",
+                        self.workspaces.len() - 1
+                    ))
+                } else if *new_index as usize == index {
+                    CommandResult::Unchanged
+                } else {
+                    let current_workspace_name =
+                        self.workspaces[self.current_workspace].name.clone();
+                    let workspace = self.workspaces.remove(index);
+                    self.workspaces.insert(*new_index as usize, workspace);
+                    self.current_workspace = self
+                        .workspaces
+                        .iter()
+                        .position(|ws| ws.name == current_workspace_name)
+                        .unwrap();
+                    self.set_root_window_desktop_properties();
+                    CommandResult::Changed
+                }
+            }
+            ("rename_workspace:", [Argument::String(name)]) => {
+                if *name == self.workspaces[index].name {
+                    return CommandResult::Unchanged;
+                }
+                match self.check_new_workspace_name(name) {
+                    Ok(()) => {
+                        for history_name in self.workspace_history.iter_mut() {
+                            if *history_name == self.workspaces[index].name {
+                                *history_name = name.clone();
+                            }
+                        }
+                        self.workspaces[index].name = name.clone();
+                        self.set_root_window_desktop_properties();
+                        CommandResult::Changed
                     }
-                    "{focused_window}" => self.workspaces[self.current_workspace]
-                        .focused_window_index
-                        .map(|index| {
-                            format!(
-                                "{}",
-                                self.workspaces[self.current_workspace].windows[index].window()
-                            )
-                        })
-                        .ok_or(()),
-                    _ => Ok(token.to_owned()),
+                    Err(message) => CommandResult::Error(message),
+                }
+            }
+            _ => self.workspaces[index].execute_command(command, args),
+        }
+    }
+
+    // Names appear in command paths, so they cannot contain a slash
+    fn check_new_workspace_name(&self, name: &str) -> Result<(), String> {
+        if name.is_empty() {
+            Err(String::from("Workspace names cannot be empty"))
+        } else if name.contains('/') {
+            Err(format!("Workspace names cannot contain '/': {}", name))
+        } else if self.workspaces.iter().any(|ws| ws.name == name) {
+            Err(format!("Workspace already exists: {}", name))
+        } else {
+            Ok(())
+        }
+    }
+
+    // The current workspace, unless that is the one being deleted
+    fn default_destination_workspace(&self, index: usize) -> usize {
+        if index != self.current_workspace {
+            self.current_workspace
+        } else {
+            self.previous_workspace()
+                .filter(|previous_workspace| *previous_workspace != index)
+                .unwrap_or(if index == 0 { 1 } else { index - 1 })
+        }
+    }
+
+    fn delete_workspace(&mut self, index: usize, new_workspace: usize) -> CommandResult {
+        if index == self.current_workspace {
+            self.set_workspace(new_workspace);
+        }
+
+        let windows = std::mem::take(&mut self.workspaces[index].windows);
+        for window_data in windows.into_iter().rev() {
+            if new_workspace == self.current_workspace {
+                xcb::map_window(connection(), window_data.window());
+            }
+            self.workspaces[new_workspace].add_window_data(window_data);
+        }
+
+        let workspace = self.workspaces.remove(index);
+        self.workspace_history
+            .retain(|name| *name != workspace.name);
+        if self.current_workspace > index {
+            self.current_workspace -= 1;
+        }
+        self.set_root_window_desktop_properties();
+        connection().flush();
+        CommandResult::Changed
+    }
+
+    // The settings are shared by all workspaces, so they aren't part of any workspace's undo
+    fn apply_layout_settings(&mut self) -> CommandResult {
+        for workspace in self.workspaces.iter_mut() {
+            workspace.set_layout_settings(self.layout_settings);
+        }
+        CommandResult::Changed
+    }
+
+    fn workspace_names(&self) -> Vec<&str> {
+        self.workspaces.iter().map(|ws| ws.name.as_str()).collect()
+    }
+
+    // The full catalog supplies the argument types, including those of commands that are
+    // unavailable right now. Unknown commands get their words as strings, and dispatching them
+    // decides what happens.
+    fn bind_arguments(&mut self, invocation: &Invocation) -> Result<Vec<Argument>, String> {
+        let words = invocation
+            .words
+            .iter()
+            .map(|word| self.expand_word(word))
+            .collect::<Result<Vec<String>, String>>()?;
+        match self.find_command(&invocation.command) {
+            Some(descriptor) => descriptor.bind(&words),
+            None => Ok(words.into_iter().map(Argument::String).collect()),
+        }
+    }
+
+    fn expand_word(&mut self, word: &Word) -> Result<String, String> {
+        let mut text = String::new();
+        for part in &word.parts {
+            match part {
+                WordPart::Text(part_text) => text.push_str(part_text),
+                WordPart::Placeholder(name) => text.push_str(&self.expand_placeholder(name)?),
+            }
+        }
+        Ok(text)
+    }
+
+    fn expand_placeholder(&mut self, name: &str) -> Result<String, String> {
+        match name {
+            "selected_window" => {
+                self.do_command("layout/show_window_selector_labels", &[]);
+                let selected_label = self.run_keygrab_event_loop();
+                self.do_command("layout/hide_window_selector_labels", &[]);
+                selected_label
+                    .and_then(|label| {
+                        self.workspaces[self.current_workspace]
+                            .windows
+                            .iter()
+                            .find(|w| w.selector_label == label)
+                            .map(|w| format!("{}", w.window()))
+                    })
+                    .ok_or_else(|| String::from("No window was selected"))
+            }
+            "focused_window" => self.workspaces[self.current_workspace]
+                .focused_window_index
+                .map(|index| {
+                    format!(
+                        "{}",
+                        self.workspaces[self.current_workspace].windows[index].window()
+                    )
                 })
-                .collect::<Result<Vec<String>, ()>>()
-            {
-                self.do_command(
-                    command,
-                    &args.iter().map(|s| s.as_str()).collect::<Vec<&str>>(),
-                );
+                .ok_or_else(|| String::from("No window is focused")),
+            name if name.starts_with("class:") => {
+                let class_name = &name["class:".len()..];
+                self.workspaces[self.current_workspace]
+                    .windows
+                    .iter()
+                    .find(|w| {
+                        get_ascii_strings_property(w.window(), xcb::ATOM_WM_CLASS)
+                            .iter()
+                            .any(|name| name == class_name)
+                    })
+                    .map(|w| format!("{}", w.window()))
+                    .ok_or_else(|| format!("No window with class {}", class_name))
+            }
+            "window_under_pointer" => self
+                .window_under_pointer()
+                .map(|window| format!("{}", window))
+                .ok_or_else(|| String::from("No window under the pointer")),
+            "clicked_window" => self
+                .run_click_event_loop()
+                .map(|window| format!("{}", window))
+                .ok_or_else(|| String::from("No window was clicked")),
+            "selected_workspace" => {
+                let names = self.workspaces.iter().map(|ws| ws.name.clone()).collect();
+                self.run_choice_event_loop(names)
+                    .ok_or_else(|| String::from("No workspace was selected"))
+            }
+            "selected_layout" => {
+                let names = self.workspaces[self.current_workspace]
+                    .layouts
+                    .iter()
+                    .map(|l| l.name())
+                    .collect();
+                self.run_choice_event_loop(names)
+                    .ok_or_else(|| String::from("No layout was selected"))
+            }
+            name if name.starts_with("prompt:") => {
+                let label = &name["prompt:".len()..];
+                self.run_text_entry_event_loop(label)
+                    .ok_or_else(|| String::from("The prompt was cancelled"))
             }
+            // Braces that aren't a placeholder are kept as they were written
+            _ => Ok(format!("{{{}}}", name)),
         }
     }
 
@@ -569,6 +1400,12 @@ impl WindowManager {
         match self.classify_window(window) {
             None => self.unmanaged_windows.push(window),
             Some(is_floating) => {
+                // title changes are shown in decorations
+                xcb::change_window_attributes(
+                    connection(),
+                    window,
+                    &[(xcb::CW_EVENT_MASK, xcb::EVENT_MASK_PROPERTY_CHANGE)],
+                );
                 // TODO: use symbolic representations in the config
                 xcb::grab_button(
                     connection(),
@@ -645,42 +1482,171 @@ impl WindowManager {
 }
 
 impl Commands for WindowManager {
-    fn get_commands(&self) -> Vec<String> {
-        let mut commands = self.workspaces[self.current_workspace].get_commands();
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands = self.get_workspace_commands(self.current_workspace);
         if self.workspaces.len() > 1 {
-            commands.push(String::from("move_focused_window_to_workspace_named:"));
-            commands.push(String::from("switch_to_workspace_named:"));
+            commands.push(
+                CommandDescriptor::new("switch_to_workspace_named:", "Switch to another workspace")
+                    .with_parameter(
+                        Parameter::new("workspace", ArgumentType::String)
+                            .with_allowed_values(&self.workspace_names()),
+                    ),
+            );
+            commands.push(CommandDescriptor::new(
+                "switch_to_next_workspace",
+                "Switch to the next workspace in order",
+            ));
+            commands.push(
+                CommandDescriptor::new(
+                    "delete_workspace:",
+                    "Delete a workspace, moving its windows to the current or another workspace",
+                )
+                .with_parameter(
+                    Parameter::new("workspace", ArgumentType::String)
+                        .with_allowed_values(&self.workspace_names()),
+                )
+                .with_parameter(
+                    Parameter::new("destination", ArgumentType::String)
+                        .with_allowed_values(&self.workspace_names())
+                        .optional(),
+                ),
+            );
+            commands.push(CommandDescriptor::new(
+                "switch_to_previous_workspace_in_order",
+                "Switch to the previous workspace in order",
+            ));
         }
-        commands.push(String::from("quit"));
+        if self.previous_workspace().is_some() {
+            commands.push(CommandDescriptor::new(
+                "switch_to_previous_workspace",
+                "Switch back to the most recently used workspace",
+            ));
+        }
+        commands.push(
+            CommandDescriptor::new("create_workspace:", "Add a workspace after the others")
+                .with_parameter(Parameter::new("name", ArgumentType::String)),
+        );
+        commands.push(CommandDescriptor::new(
+            "toggle_gaps",
+            if self.layout_settings.are_gaps_enabled {
+                "Remove the gaps around and between windows"
+            } else {
+                "Restore the gaps around and between windows"
+            },
+        ));
+        commands.push(CommandDescriptor::new(
+            "toggle_borders",
+            if self.layout_settings.are_borders_enabled {
+                "Remove the borders of windows"
+            } else {
+                "Restore the borders of windows"
+            },
+        ));
+        commands.push(CommandDescriptor::new("quit", "Quit ceramic"));
+        commands.extend(self.macros.iter().map(|m| m.descriptor().clone()));
         commands
     }
 
-    fn execute_command(&mut self, command: &str, args: &[&str]) -> bool {
-        match command {
-            "move_focused_window_to_workspace_named:" => {
-                match self.workspaces.iter().position(|ws| ws.name == args[0]) {
-                    Some(new_workspace) if new_workspace != self.current_workspace => {
-                        if let Some(window_data) =
-                            self.workspaces[self.current_workspace].remove_focused_window()
-                        {
-                            xcb::unmap_window(connection(), window_data.window());
-                            self.workspaces[new_workspace].add_window_data(window_data);
-                            true
-                        } else {
-                            false
+    fn get_all_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands = self.get_commands();
+        extend_commands(
+            &mut commands,
+            self.workspaces[self.current_workspace].get_all_commands(),
+        );
+        commands
+    }
+
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
+        if command.starts_with("workspace/") {
+            return match self.split_workspace_path(command) {
+                Some((index, rest)) => self.execute_workspace_command(index, rest, args),
+                None => CommandResult::Error(format!("Unknown workspace in {}", command)),
+            };
+        }
+        match (command, args) {
+            ("switch_to_workspace_named:", [Argument::String(name)]) => {
+                match self.workspaces.iter().position(|ws| ws.name == *name) {
+                    Some(new_workspace)
+                        if new_workspace == self.current_workspace
+                            && self.configuration.workspace_back_and_forth() =>
+                    {
+                        match self.previous_workspace() {
+                            Some(previous_workspace) => self.set_workspace(previous_workspace),
+                            None => CommandResult::Unchanged,
                         }
                     }
-                    _ => false,
+                    Some(new_workspace) => self.set_workspace(new_workspace),
+                    None => CommandResult::Error(format!("Unknown workspace: {}", name)),
                 }
             }
-            "switch_to_workspace_named:" => {
-                match self.workspaces.iter().position(|ws| ws.name == args[0]) {
-                    Some(new_workspace) => self.set_workspace(new_workspace),
-                    _ => false,
+            ("switch_to_previous_workspace", []) => match self.previous_workspace() {
+                Some(previous_workspace) => self.set_workspace(previous_workspace),
+                None => CommandResult::Unchanged,
+            },
+            ("switch_to_next_workspace", []) => match self.workspace_in_order(true) {
+                Some(new_workspace) => self.set_workspace(new_workspace),
+                None => CommandResult::Unchanged,
+            },
+            ("switch_to_previous_workspace_in_order", []) => match self.workspace_in_order(false) {
+                Some(new_workspace) => self.set_workspace(new_workspace),
+                None => CommandResult::Unchanged,
+            },
+            ("create_workspace:", [Argument::String(name)]) => {
+                match self.check_new_workspace_name(name) {
+                    Ok(()) => {
+                        let mut workspace = self.configuration.workspace(name);
+                        workspace.set_layout_settings(self.layout_settings);
+                        self.workspaces.push(workspace);
+                        self.set_root_window_desktop_properties();
+                        CommandResult::Changed
+                    }
+                    Err(message) => CommandResult::Error(message),
+                }
+            }
+            ("toggle_gaps", []) => {
+                self.layout_settings.are_gaps_enabled = !self.layout_settings.are_gaps_enabled;
+                self.apply_layout_settings()
+            }
+            ("toggle_borders", []) => {
+                self.layout_settings.are_borders_enabled =
+                    !self.layout_settings.are_borders_enabled;
+                self.apply_layout_settings()
+            }
+            ("delete_workspace:", [Argument::String(name)]) => {
+                match self.workspaces.iter().position(|ws| ws.name == *name) {
+                    Some(_) if self.workspaces.len() == 1 => {
+                        CommandResult::error("Cannot delete the only workspace")
+                    }
+                    Some(index) => {
+                        let new_workspace = self.default_destination_workspace(index);
+                        self.delete_workspace(index, new_workspace)
+                    }
+                    None => CommandResult::Error(format!("Unknown workspace: {}", name)),
+                }
+            }
+            ("delete_workspace:", [Argument::String(name), Argument::String(destination)]) => {
+                let position = |name: &str| self.workspaces.iter().position(|ws| ws.name == name);
+                match (position(name), position(destination)) {
+                    (Some(index), Some(new_workspace)) if new_workspace != index => {
+                        self.delete_workspace(index, new_workspace)
+                    }
+                    (Some(_), Some(_)) => {
+                        CommandResult::error("Cannot move windows to the workspace being deleted")
+                    }
+                    (None, _) => CommandResult::Error(format!("Unknown workspace: {}", name)),
+                    (_, None) => {
+                        CommandResult::Error(format!("Unknown workspace: {}", destination))
+                    }
                 }
             }
-            "quit" => false,
-            _ => self.workspaces[self.current_workspace].execute_command(command, args),
+            ("quit", []) => {
+                self.exit_code = Some(0);
+                CommandResult::Unchanged
+            }
+            _ => match self.macros.iter().position(|m| m.name() == command) {
+                Some(index) => self.execute_macro(index, args),
+                None => self.execute_workspace_command(self.current_workspace, command, args),
+            },
         }
     }
 }
@@ -1,5 +1,12 @@
 use super::{
-    artist::Artist, commands::Commands, connection::*, layout::layout_root::LayoutRoot, layout::*,
+    artist::Artist,
+    commands::{
+        extend_commands, Argument, ArgumentType, CommandDescriptor, CommandResult, Commands,
+        Parameter,
+    },
+    connection::*,
+    layout::layout_root::LayoutRoot,
+    layout::*,
     window_data::WindowData,
 };
 
@@ -11,10 +18,22 @@ pub struct Workspace {
     pub windows: Vec<WindowData>, // top .. bottom (floating .. tiled) ordering
     pub number_of_floating_windows: usize,
     pub focused_window_index: Option<usize>,
+    undo_history: Vec<Arrangement>,
+    redo_history: Vec<Arrangement>,
+    gesture_arrangement: Option<Arrangement>, // as of the start of the gesture in progress
+}
+
+// The parts of a workspace that undo and redo restore. Focus is deliberately excluded.
+#[derive(Debug, Clone, PartialEq)]
+struct Arrangement {
+    windows: Vec<(xcb::Window, bool, f64)>, // (window, is_floating, weight) in workspace order
+    current_layout: usize,
+    layout_states: Vec<LayoutState>,
 }
 
 impl Workspace {
     pub fn new(name: &str, layouts: Vec<LayoutRoot>) -> Workspace {
+        assert!(!layouts.is_empty(), "Workspace {} has no layouts", name);
         Workspace {
             name: name.into(),
             is_visible: false,
@@ -23,6 +42,23 @@ impl Workspace {
             windows: Default::default(),
             number_of_floating_windows: 0,
             focused_window_index: None,
+            undo_history: Default::default(),
+            redo_history: Default::default(),
+            gesture_arrangement: None,
+        }
+    }
+
+    // Unknown layout names leave the first layout current
+    pub fn with_initial_layout(mut self, layout_name: &str) -> Workspace {
+        if let Some(index) = self.layouts.iter().position(|l| l.name() == layout_name) {
+            self.current_layout = index;
+        }
+        self
+    }
+
+    pub fn set_layout_settings(&mut self, settings: LayoutSettings) {
+        for layout in self.layouts.iter_mut() {
+            layout.set_settings(settings);
         }
     }
 
@@ -119,6 +155,8 @@ impl Workspace {
     }
 
     pub fn update_layout(&mut self, bounds: &Bounds) -> Vec<Box<dyn Artist>> {
+        let focused_window = self.focused_window();
+        self.layouts[self.current_layout].set_focused_window(focused_window);
         let (new_windows, artists) =
             self.layouts[self.current_layout].layout(bounds, self.windows.clone());
 
@@ -153,6 +191,122 @@ impl Workspace {
         artists
     }
 
+    pub fn get_boundaries(&self, bounds: &Bounds) -> Vec<Boundary> {
+        self.layouts[self.current_layout]
+            .get_boundaries(bounds, &self.windows)
+            .iter()
+            .map(|b| b.prefixed("layout/"))
+            .collect()
+    }
+
+    const MAXIMUM_HISTORY: usize = 50;
+    const WEIGHT_STEP: f64 = 0.25;
+    const MINIMUM_WEIGHT: f64 = 0.25;
+    const MAXIMUM_WEIGHT: f64 = 4.0;
+
+    fn arrangement(&self) -> Arrangement {
+        Arrangement {
+            windows: self
+                .windows
+                .iter()
+                .map(|w| (w.window(), w.is_floating, w.weight))
+                .collect(),
+            current_layout: self.current_layout,
+            layout_states: self.layouts.iter().map(|l| l.get_state()).collect(),
+        }
+    }
+
+    // Windows that have appeared since the arrangement was recorded keep their relative
+    // position at the end of their layer
+    fn restore_arrangement(&mut self, arrangement: &Arrangement) {
+        let focused_window = self.focused_window_index.map(|i| self.windows[i].window());
+
+        for window in self.windows.iter_mut() {
+            if let Some((_, is_floating, weight)) = arrangement
+                .windows
+                .iter()
+                .find(|(w, _, _)| *w == window.window())
+            {
+                window.is_floating = *is_floating;
+                window.weight = *weight;
+            }
+        }
+        self.windows.sort_by_key(|window| {
+            (
+                !window.is_floating,
+                arrangement
+                    .windows
+                    .iter()
+                    .position(|(w, _, _)| *w == window.window())
+                    .unwrap_or(usize::MAX),
+            )
+        });
+        self.number_of_floating_windows = self.windows.iter().filter(|w| w.is_floating).count();
+
+        self.current_layout = arrangement.current_layout.min(self.layouts.len() - 1);
+        for (layout, state) in self.layouts.iter_mut().zip(&arrangement.layout_states) {
+            layout.set_state(state);
+        }
+
+        let focused_window_index = focused_window.and_then(|w| self.find_window(w));
+        self.set_focused_window(focused_window_index);
+    }
+
+    fn record_history(&mut self, arrangement: Arrangement) {
+        self.undo_history.push(arrangement);
+        if self.undo_history.len() > Self::MAXIMUM_HISTORY {
+            self.undo_history.remove(0);
+        }
+        self.redo_history.clear();
+    }
+
+    // The commands executed during a gesture, e.g. dragging a boundary, are undone as one
+    pub fn begin_gesture(&mut self) {
+        self.gesture_arrangement = Some(self.arrangement());
+    }
+
+    pub fn end_gesture(&mut self) {
+        if let Some(arrangement) = self.gesture_arrangement.take() {
+            if self.arrangement() != arrangement {
+                self.record_history(arrangement);
+            }
+        }
+    }
+
+    fn undo(&mut self) -> CommandResult {
+        match self.undo_history.pop() {
+            Some(arrangement) => {
+                self.redo_history.push(self.arrangement());
+                self.restore_arrangement(&arrangement);
+                CommandResult::Changed
+            }
+            None => CommandResult::error("Nothing to undo"),
+        }
+    }
+
+    fn redo(&mut self) -> CommandResult {
+        match self.redo_history.pop() {
+            Some(arrangement) => {
+                self.undo_history.push(self.arrangement());
+                self.restore_arrangement(&arrangement);
+                CommandResult::Changed
+            }
+            None => CommandResult::error("Nothing to redo"),
+        }
+    }
+
+    // Weights only matter to the layouts that divide space by them
+    fn change_weight(&mut self, index: usize, change: f64) -> CommandResult {
+        if !self.layouts[self.current_layout].is_weighted(self.windows[index].window()) {
+            return CommandResult::Unchanged;
+        }
+        let window = &mut self.windows[index];
+        let weight = (window.weight + change).clamp(Self::MINIMUM_WEIGHT, Self::MAXIMUM_WEIGHT);
+        let is_changed = weight != window.weight;
+        window.weight = weight;
+        CommandResult::changed_if(is_changed)
+    }
+
     fn remove_window_index(&mut self, index: usize) -> WindowData {
         let old_window = self.windows.remove(index);
         if old_window.is_floating {
@@ -207,6 +361,11 @@ impl Workspace {
             .position(|window_data| window_data.window() == window)
     }
 
+    fn focused_window(&self) -> Option<xcb::Window> {
+        self.focused_window_index
+            .map(|index| self.windows[index].window())
+    }
+
     fn set_focused_window(&mut self, w: Option<usize>) {
         self.focused_window_index = w;
         self.synchronize_focused_window_with_os();
@@ -218,7 +377,7 @@ impl Workspace {
         }
 
         let connection = connection();
-        let screen = connection.get_setup().roots().nth(0).unwrap();
+        let screen = screen();
         if let Some(index) = self.focused_window_index {
             xcb::set_input_focus(
                 &connection,
@@ -244,34 +403,102 @@ impl Workspace {
 }
 
 impl Commands for Workspace {
-    fn get_commands(&self) -> Vec<String> {
-        let mut commands: Vec<String> = self.layouts[self.current_layout]
+    fn get_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands: Vec<CommandDescriptor> = self.layouts[self.current_layout]
             .get_commands()
             .iter()
-            .map(|c| format!("layout/{}", c))
+            .map(|c| c.prefixed("layout/"))
             .collect();
         if self.layouts.len() > 1 {
-            commands.push(String::from("switch_to_next_layout"));
-            commands.push(String::from("switch_to_previous_layout"));
-            commands.push(String::from("switch_to_layout_named:"));
+            commands.push(CommandDescriptor::new(
+                "switch_to_next_layout",
+                "Switch to the next layout",
+            ));
+            commands.push(CommandDescriptor::new(
+                "switch_to_previous_layout",
+                "Switch to the previous layout",
+            ));
+            commands.push(
+                CommandDescriptor::new("switch_to_layout_named:", "Switch to the named layout")
+                    .with_parameter(
+                        Parameter::new("layout", ArgumentType::String).with_allowed_values(
+                            &self.layouts.iter().map(|l| l.name()).collect::<Vec<_>>(),
+                        ),
+                    ),
+            );
+        }
+        if !self.undo_history.is_empty() {
+            commands.push(CommandDescriptor::new(
+                "undo",
+                "Undo the last change to the window arrangement",
+            ));
+        }
+        if !self.redo_history.is_empty() {
+            commands.push(CommandDescriptor::new(
+                "redo",
+                "Redo the last undone change to the window arrangement",
+            ));
+        }
+        if self.windows.iter().any(|w| w.weight != 1.0) {
+            commands.push(CommandDescriptor::new(
+                "reset_weights",
+                "Give all windows the same space",
+            ));
         }
         if !self.windows.is_empty() {
             if let Some(index) = self.focused_window_index {
-                if self.windows[index].is_floating {
-                    commands.push(String::from("tile_window:"));
-                } else {
-                    commands.push(String::from("float_window:"));
-                }
+                let is_floating = self.windows[index].is_floating;
+                commands.extend(self.window_commands().into_iter().filter(
+                    |c| match c.name.as_str() {
+                        "tile_window:" => is_floating,
+                        "float_window:" => !is_floating,
+                        _ => self.windows.len() > 1,
+                    },
+                ));
                 // TODO: this should be the count of *focusable* windows
                 if self.windows.len() > 1 {
-                    commands.push(String::from("focus_on_window:"));
-                    commands.push(String::from("move_focused_window_to_head"));
-                    commands.push(String::from("move_focused_window_forward"));
-                    commands.push(String::from("move_focused_window_backward"));
-                    commands.push(String::from("focus_on_next_window_in_layer"));
-                    commands.push(String::from("focus_on_next_window"));
-                    commands.push(String::from("focus_on_previous_window_in_layer"));
-                    commands.push(String::from("focus_on_previous_window"));
+                    commands.push(CommandDescriptor::new(
+                        "move_focused_window_to_head",
+                        "Move the focused window to the head of its layer",
+                    ));
+                    commands.push(CommandDescriptor::new(
+                        "move_focused_window_forward",
+                        "Move the focused window forward in its layer",
+                    ));
+                    commands.push(CommandDescriptor::new(
+                        "move_focused_window_backward",
+                        "Move the focused window backward in its layer",
+                    ));
+                    commands.push(CommandDescriptor::new(
+                        "focus_on_next_window_in_layer",
+                        "Focus on the next window in the same layer",
+                    ));
+                    commands.push(CommandDescriptor::new(
+                        "focus_on_next_window",
+                        "Focus on the next window",
+                    ));
+                    commands.push(CommandDescriptor::new(
+                        "focus_on_previous_window_in_layer",
+                        "Focus on the previous window in the same layer",
+                    ));
+                    commands.push(CommandDescriptor::new(
+                        "focus_on_previous_window",
+                        "Focus on the previous window",
+                    ));
+                }
+                if self.layouts[self.current_layout].is_weighted(self.windows[index].window()) {
+                    if self.windows[index].weight < Self::MAXIMUM_WEIGHT {
+                        commands.push(CommandDescriptor::new(
+                            "grow_focused_window",
+                            "Give the focused window more space",
+                        ));
+                    }
+                    if self.windows[index].weight > Self::MINIMUM_WEIGHT {
+                        commands.push(CommandDescriptor::new(
+                            "shrink_focused_window",
+                            "Give the focused window less space",
+                        ));
+                    }
                 }
                 commands.extend(self.windows[index].get_commands().into_iter());
             }
@@ -279,38 +506,91 @@ impl Commands for Workspace {
         commands
     }
 
-    fn execute_command(&mut self, command: &str, args: &[&str]) -> bool {
+    fn get_all_commands(&self) -> Vec<CommandDescriptor> {
+        let mut commands = self.get_commands();
+        extend_commands(&mut commands, self.window_commands());
+        commands
+    }
+
+    fn execute_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
+        match command {
+            "undo" => self.undo(),
+            "redo" => self.redo(),
+            _ => {
+                let arrangement = self.arrangement();
+                let result = self.dispatch_command(command, args);
+                if result.needs_layout()
+                    && self.gesture_arrangement.is_none()
+                    && self.arrangement() != arrangement
+                {
+                    self.record_history(arrangement);
+                }
+                result
+            }
+        }
+    }
+}
+
+impl Workspace {
+    fn window_commands(&self) -> Vec<CommandDescriptor> {
+        let window_parameter = Parameter::new("window", ArgumentType::Window)
+            .with_allowed_values(&self.windows.iter().map(|w| w.window()).collect::<Vec<_>>());
+        vec![
+            CommandDescriptor::new("tile_window:", "Tile a floating window")
+                .with_parameter(window_parameter.clone()),
+            CommandDescriptor::new("float_window:", "Float a tiled window")
+                .with_parameter(window_parameter.clone()),
+            CommandDescriptor::new("focus_on_window:", "Focus on a window")
+                .with_parameter(window_parameter),
+        ]
+    }
+
+    fn dispatch_command(&mut self, command: &str, args: &[Argument]) -> CommandResult {
         if command.starts_with("layout/") {
+            let focused_window = self.focused_window();
+            self.layouts[self.current_layout].set_focused_window(focused_window);
             self.layouts[self.current_layout].execute_command(command.split_at(7).1, args)
         } else {
             match command {
                 "switch_to_next_layout" => {
                     self.current_layout = (self.current_layout + 1) % self.layouts.len();
-                    true
+                    CommandResult::Changed
                 }
                 "switch_to_previous_layout" => {
                     self.current_layout =
                         (self.current_layout + self.layouts.len() - 1) % self.layouts.len();
-                    true
+                    CommandResult::Changed
                 }
-                "switch_to_layout_named:" => {
-                    match self.layouts.iter().position(|l| l.name() == args[0]) {
-                        Some(index) => {
-                            self.current_layout = index;
-                            true
+                "switch_to_layout_named:" => match args {
+                    [Argument::String(name)] => {
+                        match self.layouts.iter().position(|l| l.name() == *name) {
+                            Some(index) => {
+                                self.current_layout = index;
+                                CommandResult::Changed
+                            }
+                            None => CommandResult::Error(format!("Unknown layout: {}", name)),
                         }
-                        None => false,
                     }
+                    _ => CommandResult::invalid_arguments(command),
+                },
+                "reset_weights" => {
+                    let is_changed = self.windows.iter().any(|w| w.weight != 1.0);
+                    for window in self.windows.iter_mut() {
+                        window.weight = 1.0;
+                    }
+                    CommandResult::changed_if(is_changed)
                 }
-                "focus_on_window:" => match args[0].parse::<u32>() {
-                    Ok(window) => match self.windows.iter().position(|w| w.window() == window) {
-                        Some(index) => {
-                            self.set_focused_window(Some(index));
-                            true
+                "focus_on_window:" => match args {
+                    [Argument::Window(window)] => {
+                        match self.windows.iter().position(|w| w.window() == *window) {
+                            Some(index) => {
+                                self.set_focused_window(Some(index));
+                                CommandResult::Changed
+                            }
+                            None => CommandResult::error("Unknown window"),
                         }
-                        None => false,
-                    },
-                    Err(_) => false,
+                    }
+                    _ => CommandResult::invalid_arguments(command),
                 },
                 _ => match self.focused_window_index {
                     Some(index) => match command {
@@ -322,33 +602,35 @@ impl Commands for Workspace {
                             };
                             self.windows.swap(index, new_index);
                             self.set_focused_window(Some(new_index));
-                            true
+                            CommandResult::Changed
                         }
                         "move_focused_window_forward" => {
                             let new_index = self.wrapped_next_index_in_layer(index);
                             self.windows.swap(index, new_index);
                             self.set_focused_window(Some(new_index));
-                            true
+                            CommandResult::Changed
                         }
                         "move_focused_window_backward" => {
                             let new_index = self.wrapped_previous_index_in_layer(index);
                             self.windows.swap(index, new_index);
                             self.set_focused_window(Some(new_index));
-                            true
+                            CommandResult::Changed
                         }
                         "focus_on_next_window" => {
                             self.set_focused_window(Some(self.wrapped_next_index_in_layer(index)));
-                            true
+                            CommandResult::Changed
                         }
                         "focus_on_previous_window" => {
                             self.set_focused_window(Some(
                                 self.wrapped_previous_index_in_layer(index),
                             ));
-                            true
+                            CommandResult::Changed
                         }
-                        "float_window:" => match args[0].parse::<u32>() {
-                            Ok(window) => {
-                                match self.windows.iter().position(|w| w.window() == window) {
+                        "grow_focused_window" => self.change_weight(index, Self::WEIGHT_STEP),
+                        "shrink_focused_window" => self.change_weight(index, -Self::WEIGHT_STEP),
+                        "float_window:" => match args {
+                            [Argument::Window(window)] => {
+                                match self.windows.iter().position(|w| w.window() == *window) {
                                     Some(index) => {
                                         if !self.windows[index].is_floating {
                                             self.windows[index].is_floating = true;
@@ -357,19 +639,19 @@ impl Commands for Workspace {
                                             let wd = self.windows.remove(index);
                                             self.windows.insert(new_index, wd);
                                             self.set_focused_window(Some(new_index));
-                                            true
+                                            CommandResult::Changed
                                         } else {
-                                            false
+                                            CommandResult::Unchanged
                                         }
                                     }
-                                    None => false,
+                                    None => CommandResult::error("Unknown window"),
                                 }
                             }
-                            Err(_) => false,
+                            _ => CommandResult::invalid_arguments(command),
                         },
-                        "tile_window:" => match args[0].parse::<u32>() {
-                            Ok(window) => {
-                                match self.windows.iter().position(|w| w.window() == window) {
+                        "tile_window:" => match args {
+                            [Argument::Window(window)] => {
+                                match self.windows.iter().position(|w| w.window() == *window) {
                                     Some(index) => {
                                         if self.windows[index].is_floating {
                                             self.windows[index].is_floating = false;
@@ -378,19 +660,19 @@ impl Commands for Workspace {
                                             let wd = self.windows.remove(index);
                                             self.windows.insert(new_index, wd);
                                             self.set_focused_window(Some(new_index));
-                                            true
+                                            CommandResult::Changed
                                         } else {
-                                            false
+                                            CommandResult::Unchanged
                                         }
                                     }
-                                    None => false,
+                                    None => CommandResult::error("Unknown window"),
                                 }
                             }
-                            Err(_) => false,
+                            _ => CommandResult::invalid_arguments(command),
                         },
                         _ => self.windows[index].execute_command(command, args),
                     },
-                    None => false,
+                    None => CommandResult::error("No window focused"),
                 },
             }
         }